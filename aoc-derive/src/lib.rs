@@ -0,0 +1,146 @@
+//! `#[derive(FromLine)]`, see `aoc::input::FromLine`.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{Data, DataStruct, DeriveInput, Fields, LitStr, parse_macro_input};
+
+/// Parses a struct from a line matching its `#[line("...")]` pattern, where
+/// each `{field}` is parsed with `FromStr` and the text between them must
+/// appear as is. A separator of only spaces matches any run of whitespace.
+#[proc_macro_derive(FromLine, attributes(line))]
+pub fn derive_from_line(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    expand(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let Data::Struct(DataStruct {
+        fields: Fields::Named(fields),
+        ..
+    }) = &input.data
+    else {
+        return Err(syn::Error::new_spanned(
+            input,
+            "FromLine needs a struct with named fields",
+        ));
+    };
+
+    let Some(attr) = input.attrs.iter().find(|a| a.path().is_ident("line")) else {
+        return Err(syn::Error::new_spanned(
+            &input.ident,
+            "missing a #[line(\"...\")] pattern",
+        ));
+    };
+    let pattern = attr.parse_args::<LitStr>()?;
+    let (literals, names) =
+        split_pattern(&pattern.value()).map_err(|e| syn::Error::new_spanned(&pattern, e))?;
+
+    let idents = fields.named.iter().filter_map(|f| f.ident.as_ref());
+    for ident in idents.clone() {
+        if !names.contains(&ident.to_string()) {
+            return Err(syn::Error::new_spanned(
+                ident,
+                format!("`{ident}` is not in the pattern"),
+            ));
+        }
+    }
+    for name in &names {
+        if !idents.clone().any(|i| i == name) {
+            return Err(syn::Error::new_spanned(
+                &pattern,
+                format!("no field `{name}`"),
+            ));
+        }
+    }
+
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let n = names.len();
+    let vars = names
+        .iter()
+        .map(|name| format_ident!("{name}"))
+        .collect::<Vec<_>>();
+
+    Ok(quote! {
+        impl #impl_generics ::aoc::input::FromLine for #ident #ty_generics #where_clause {
+            fn from_line(line: &str) -> ::std::result::Result<Self, ::std::string::String> {
+                let [#(#vars),*] = ::aoc::input::fields::<#n>(line, &[#(#literals),*], #pattern)?;
+
+                ::std::result::Result::Ok(Self {
+                    #(#vars: ::aoc::input::field(#vars, #names)?),*
+                })
+            }
+        }
+    })
+}
+
+/// Splits a pattern into the text around its fields, `"{a}|{b}"` gives
+/// `["", "|", ""]` and `["a", "b"]`.
+fn split_pattern(pattern: &str) -> Result<(Vec<String>, Vec<String>), String> {
+    let mut literals = vec![];
+    let mut names: Vec<String> = vec![];
+    let mut rest = pattern;
+    loop {
+        let Some(open) = rest.find('{') else {
+            if rest.contains('}') {
+                return Err("unmatched `}` in pattern".to_string());
+            }
+            literals.push(rest.to_string());
+
+            return Ok((literals, names));
+        };
+
+        let literal = &rest[..open];
+        if literal.contains('}') {
+            return Err("unmatched `}` in pattern".to_string());
+        }
+        if !names.is_empty() && literal.is_empty() {
+            return Err("fields need some text between them".to_string());
+        }
+
+        let Some(close) = rest[open..].find('}') else {
+            return Err("unclosed `{` in pattern".to_string());
+        };
+        let name = &rest[open + 1..open + close];
+        if syn::parse_str::<syn::Ident>(name).is_err() {
+            return Err(format!("`{name}` is not a field name"));
+        }
+        if names.iter().any(|n| n == name) {
+            return Err(format!("`{name}` appears twice"));
+        }
+
+        literals.push(literal.to_string());
+        names.push(name.to_string());
+        rest = &rest[open + close + 1..];
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::split_pattern;
+
+    #[test]
+    fn test_split_pattern() {
+        let strings = |v: &[&str]| v.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+
+        assert_eq!(
+            Ok((strings(&["", "|", ""]), strings(&["before", "after"]))),
+            split_pattern("{before}|{after}")
+        );
+        assert_eq!(
+            Ok((strings(&["p=<", ",", ">"]), strings(&["x", "y"]))),
+            split_pattern("p=<{x},{y}>")
+        );
+        assert_eq!(Ok((strings(&["fixed"]), vec![])), split_pattern("fixed"));
+
+        assert!(split_pattern("{a}{b}").is_err());
+        assert!(split_pattern("{a} {a}").is_err());
+        assert!(split_pattern("{a").is_err());
+        assert!(split_pattern("a}").is_err());
+        assert!(split_pattern("{1a}").is_err());
+    }
+}
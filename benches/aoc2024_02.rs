@@ -1,18 +1,22 @@
-use aoc::aoc2024::day02;
+use aoc::{aoc2024::day02, context::Context};
 use criterion::{criterion_group, criterion_main, Criterion};
 
 fn part_one(c: &mut Criterion) {
     let report = day02::Report::new_from_data().unwrap();
-    c.bench_function("sum_of_safe_reports", |b| {
+    c.bench_function("aoc2024_02/sum_of_safe_reports", |b| {
         b.iter(|| report.sum_of_safe_reports())
     });
 }
 
 fn part_two(c: &mut Criterion) {
     let report = day02::Report::new_from_data().unwrap();
-    c.bench_function("sum_of_dampened_reports", |b| {
+    c.bench_function("aoc2024_02/sum_of_dampened_reports", |b| {
         b.iter(|| report.sum_of_dampened_reports())
     });
+    let ctx = Context::default();
+    c.bench_function("aoc2024_02/sum_of_dampened_reports_fast", |b| {
+        b.iter(|| report.sum_of_dampened_reports_fast(&ctx))
+    });
 }
 
 criterion_group!(benches, part_one, part_two);
@@ -3,7 +3,7 @@ use criterion::{criterion_group, criterion_main, Criterion};
 
 fn part_one(c: &mut Criterion) {
     let computer = day03::Computer::new_from_data().unwrap();
-    c.bench_function("do_multiplication", |b| {
+    c.bench_function("aoc2024_03/do_multiplication", |b| {
         b.iter(|| computer.do_multiplication())
     });
 }
@@ -11,7 +11,7 @@ fn part_one(c: &mut Criterion) {
 fn part_two(c: &mut Criterion) {
     let computer = day03::Computer::new_from_data().unwrap();
 
-    c.bench_function("do_conditional_multiplication", |b| {
+    c.bench_function("aoc2024_03/do_conditional_multiplication", |b| {
         b.iter(|| computer.do_conditional_multiplication())
     });
 }
@@ -4,13 +4,15 @@ use criterion::{criterion_group, criterion_main, Criterion};
 fn part_one(c: &mut Criterion) {
     let monitor = day04::ElfMonitor::new_from_data().unwrap();
 
-    c.bench_function("count_xmas", |b| b.iter(|| monitor.count_xmas()));
+    c.bench_function("aoc2024_04/count_xmas", |b| b.iter(|| monitor.count_xmas()));
 }
 
 fn part_two(c: &mut Criterion) {
     let monitor = day04::ElfMonitor::new_from_data().unwrap();
 
-    c.bench_function("count_crossmas", |b| b.iter(|| monitor.count_crossmas()));
+    c.bench_function("aoc2024_04/count_crossmas", |b| {
+        b.iter(|| monitor.count_crossmas())
+    });
 }
 criterion_group!(benches, part_one, part_two);
 criterion_main!(benches);
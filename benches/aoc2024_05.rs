@@ -4,7 +4,7 @@ use criterion::{criterion_group, criterion_main, Criterion};
 fn part_one(c: &mut Criterion) {
     let printer = day05::ElfPrinter::new_from_data().unwrap();
 
-    c.bench_function("sum_of_correct_ordered", |b| {
+    c.bench_function("aoc2024_05/sum_of_correct_ordered", |b| {
         b.iter(|| printer.sum_of_correct_ordered())
     });
 }
@@ -12,7 +12,7 @@ fn part_one(c: &mut Criterion) {
 fn part_two(c: &mut Criterion) {
     let printer = day05::ElfPrinter::new_from_data().unwrap();
 
-    c.bench_function("sum_of_after_topological_sort", |b| {
+    c.bench_function("aoc2024_05/sum_of_after_topological_sort", |b| {
         b.iter(|| printer.sum_of_after_topological_sort())
     });
 }
@@ -0,0 +1,48 @@
+#![no_main]
+
+use std::sync::LazyLock;
+
+use aoc::{aoc2024::day03::Computer, input::Input};
+use libfuzzer_sys::fuzz_target;
+use regex::Regex;
+
+static INSTRUCTION: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"mul\((\d{1,3}),(\d{1,3})\)|do\(\)|don't\(\)").unwrap());
+
+type Muls = Vec<(i32, i32)>;
+
+/// The puzzle statement read literally, to check the hand-written scanner against.
+fn reference(memory: &str) -> (Muls, Muls) {
+    let mut all = vec![];
+    let mut enabled = vec![];
+    let mut on = true;
+    for caps in INSTRUCTION.captures_iter(memory) {
+        match &caps[0] {
+            "do()" => on = true,
+            "don't()" => on = false,
+            _ => {
+                let pair = (caps[1].parse().unwrap(), caps[2].parse().unwrap());
+                all.push(pair);
+                if on {
+                    enabled.push(pair);
+                }
+            }
+        }
+    }
+
+    (all, enabled)
+}
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(memory) = std::str::from_utf8(data) else {
+        return;
+    };
+    let input = Input::new(memory);
+    let Ok(computer) = Computer::new_from_input(&input) else {
+        return;
+    };
+
+    let (all, enabled) = reference(input.as_str());
+    assert_eq!(all, computer.find_mul());
+    assert_eq!(enabled, computer.find_mul_conditional());
+});
@@ -0,0 +1,22 @@
+#![no_main]
+
+use aoc::{context::Context, solution::solutions};
+use libfuzzer_sys::fuzz_target;
+
+// Every day must reject or accept arbitrary text without panicking, and solve
+// whatever its parser accepts without panicking either.
+fuzz_target!(|data: &[u8]| {
+    let Ok(input) = std::str::from_utf8(data) else {
+        return;
+    };
+
+    for solution in solutions() {
+        let Ok(solver) = (solution.parse_input)(input) else {
+            continue;
+        };
+
+        let ctx = Context::default();
+        let _ = solver.part_one(&ctx);
+        let _ = solver.part_two(&ctx);
+    }
+});
@@ -1,8 +1,22 @@
-use std::{collections::HashMap, io::BufRead};
+use std::collections::HashMap;
 
 use anyhow::Ok;
 
-use crate::read_input;
+use crate::{
+    budget::Budget,
+    context::Context,
+    generate::Rng,
+    input::{FromLine, Input, InputError},
+    solution::{Solve, arg},
+};
+
+/// One line of the lists.
+#[derive(FromLine)]
+#[line("{left} {right}")]
+struct Pair {
+    left: i32,
+    right: i32,
+}
 
 pub struct Location {
     left: Vec<i32>,
@@ -12,25 +26,21 @@ pub struct Location {
 
 impl Location {
     pub fn new_from_data() -> anyhow::Result<Self> {
-        let reader = read_input("2024/1.txt")?;
+        Self::new_from_input(&Input::embedded("2024/1.txt")?)
+    }
 
+    #[tracing::instrument(skip_all, fields(pairs))]
+    pub fn new_from_input(input: &Input) -> anyhow::Result<Self> {
         let mut left = vec![];
         let mut right = vec![];
         let mut right_counter = HashMap::new();
-        for line in reader.lines() {
-            let s = line?;
-            let split = s
-                .split(' ')
-                .filter_map(|v| {
-                    if v.is_empty() {
-                        None
-                    } else {
-                        Some(v.parse::<i32>().unwrap_or(0))
-                    }
-                })
-                .collect::<Vec<i32>>();
-            let l = split.first().cloned().unwrap_or_default();
-            let r = split.last().cloned().unwrap_or_default();
+        for (i, s) in input.lines().enumerate() {
+            if s.is_empty() {
+                continue;
+            }
+
+            let Pair { left: l, right: r } =
+                Pair::from_line(s).map_err(|reason| InputError::malformed(i + 1, reason))?;
 
             if let Some(v) = right_counter.get_mut(&r) {
                 *v += 1;
@@ -42,6 +52,8 @@ impl Location {
             right.push(r);
         }
 
+        tracing::Span::current().record("pairs", left.len());
+
         left.sort();
         right.sort();
 
@@ -52,39 +64,120 @@ impl Location {
         })
     }
 
-    pub fn sum_of_difference(&self) -> anyhow::Result<i32> {
+    /// Summed in `i64`, a million lines of five digit ids go past `i32`.
+    pub fn sum_of_difference(&self) -> anyhow::Result<i64> {
         Ok(self
             .left
             .iter()
-            .enumerate()
-            .map(|(k, v)| (v - self.right.get(k).unwrap()).abs())
-            .sum::<i32>())
+            .zip(&self.right)
+            .map(|(l, r)| (i64::from(*l) - i64::from(*r)).abs())
+            .sum())
     }
 
-    pub fn sum_of_similarities(&self) -> anyhow::Result<i32> {
+    pub fn sum_of_similarities(&self) -> anyhow::Result<i64> {
         Ok(self
             .left
             .iter()
-            .map(|v| (v * self.right_counter.get(v).unwrap_or(&0)).abs())
-            .sum::<i32>())
+            .map(|v| (i64::from(*v) * i64::from(*self.right_counter.get(v).unwrap_or(&0))).abs())
+            .sum())
+    }
+}
+
+impl Solve for Location {
+    fn part_one(&self, _ctx: &Context) -> anyhow::Result<String> {
+        Ok(self.sum_of_difference()?.to_string())
+    }
+
+    fn part_two(&self, _ctx: &Context) -> anyhow::Result<String> {
+        Ok(self.sum_of_similarities()?.to_string())
+    }
+
+    fn queries(&self) -> &'static [(&'static str, &'static str)] {
+        &[
+            ("count", "number of location pairs"),
+            ("pair <n>", "nth smallest left and right location"),
+            (
+                "occurrences <id>",
+                "how often a location appears in the right list",
+            ),
+        ]
+    }
+
+    fn query(&self, command: &str, args: &[&str]) -> Option<anyhow::Result<String>> {
+        match command {
+            "count" => Some(Ok(self.left.len().to_string())),
+            "pair" => Some(arg::<usize>(args, 0).and_then(|n| {
+                match (self.left.get(n), self.right.get(n)) {
+                    (Some(l), Some(r)) => Ok(format!("{l} {r}")),
+                    _ => anyhow::bail!("no pair {n}, there are {}", self.left.len()),
+                }
+            })),
+            "occurrences" => Some(arg::<i32>(args, 0).map(|id| {
+                self.right_counter
+                    .get(&id)
+                    .cloned()
+                    .unwrap_or_default()
+                    .to_string()
+            })),
+            _ => None,
+        }
     }
 }
 
-fn part_one() -> anyhow::Result<i32> {
+/// `lines` pairs of location ids, with some right ids repeating left ones.
+pub fn generate(rng: &mut Rng, lines: usize) -> String {
+    let mut left = Vec::with_capacity(lines);
+    let mut out = String::new();
+    for _ in 0..lines {
+        let l = rng.range(10_000, 100_000);
+        left.push(l);
+        let r = if rng.chance(0.3) {
+            *rng.choose(&left)
+        } else {
+            rng.range(10_000, 100_000)
+        };
+
+        out.push_str(&format!("{l}   {r}\n"));
+    }
+
+    out
+}
+
+fn part_one() -> anyhow::Result<i64> {
     let location = Location::new_from_data()?;
 
     location.sum_of_difference()
 }
 
-fn part_two() -> anyhow::Result<i32> {
+fn part_two() -> anyhow::Result<i64> {
     let location = Location::new_from_data()?;
 
     location.sum_of_similarities()
 }
 
+pub const BUDGET: Budget = Budget::millis(2, 1, 1);
+
 #[cfg(test)]
 mod test {
-    use super::{part_one, part_two};
+    use std::collections::HashMap;
+
+    use proptest::{collection::vec, prelude::*};
+
+    use super::{Location, part_one, part_two};
+    use crate::{
+        input::Input,
+        strategy::{join_spaced, number},
+    };
+
+    fn locations() -> impl Strategy<Value = (Vec<(i32, i32)>, String)> {
+        vec((number(), number()), 0..50).prop_flat_map(|pairs| {
+            let lines = pairs
+                .iter()
+                .map(|(l, r)| join_spaced(vec![l.to_string(), r.to_string()]))
+                .collect::<Vec<_>>();
+            (Just(pairs), lines.prop_map(|l| l.join("\n")))
+        })
+    }
 
     #[test]
     fn test_1_1() {
@@ -97,4 +190,61 @@ mod test {
         let ans = part_two().unwrap();
         assert_eq!(21271939, ans)
     }
+
+    proptest! {
+        #[test]
+        fn test_parse((pairs, input) in locations()) {
+            let location = Location::new_from_input(&Input::new(&input)).unwrap();
+
+            let mut left = pairs.iter().map(|p| p.0).collect::<Vec<_>>();
+            let mut right = pairs.iter().map(|p| p.1).collect::<Vec<_>>();
+            left.sort();
+            right.sort();
+            let mut counter = HashMap::new();
+            for r in &right {
+                *counter.entry(*r).or_insert(0) += 1;
+            }
+
+            prop_assert_eq!(left, location.left);
+            prop_assert_eq!(right, location.right);
+            prop_assert_eq!(counter, location.right_counter);
+        }
+    }
+
+    fn render(pairs: &[(i32, i32)]) -> String {
+        pairs
+            .iter()
+            .map(|(l, r)| format!("{l}   {r}"))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    proptest! {
+        #[test]
+        fn test_swapped_columns(pairs in vec((number(), number()), 0..50)) {
+            let swapped = pairs.iter().map(|&(l, r)| (r, l)).collect::<Vec<_>>();
+            let location = Location::new_from_input(&Input::new(&render(&pairs))).unwrap();
+            let mirrored = Location::new_from_input(&Input::new(&render(&swapped))).unwrap();
+
+            prop_assert_eq!(
+                location.sum_of_difference().unwrap(),
+                mirrored.sum_of_difference().unwrap()
+            );
+        }
+
+        #[test]
+        fn test_shuffled_lines(
+            (pairs, shuffled) in vec((number(), number()), 0..50)
+                .prop_flat_map(|p| (Just(p.clone()), Just(p).prop_shuffle()))
+        ) {
+            let location = Location::new_from_input(&Input::new(&render(&pairs))).unwrap();
+            let shuffled = Location::new_from_input(&Input::new(&render(&shuffled))).unwrap();
+
+            prop_assert_eq!(location.sum_of_difference().unwrap(), shuffled.sum_of_difference().unwrap());
+            prop_assert_eq!(
+                location.sum_of_similarities().unwrap(),
+                shuffled.sum_of_similarities().unwrap()
+            );
+        }
+    }
 }
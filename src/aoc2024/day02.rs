@@ -1,8 +1,12 @@
-use std::io::BufRead;
-
 use anyhow::Ok;
 
-use crate::read_input;
+use crate::{
+    budget::Budget,
+    context::Context,
+    generate::Rng,
+    input::{Input, InputError, ints},
+    solution::{Solve, arg},
+};
 
 pub struct Report {
     data: Vec<Vec<i32>>,
@@ -10,25 +14,25 @@ pub struct Report {
 
 impl Report {
     pub fn new_from_data() -> anyhow::Result<Report> {
-        let reader = read_input("2024/2.txt")?;
+        Self::new_from_input(&Input::embedded("2024/2.txt")?)
+    }
 
+    #[tracing::instrument(skip_all, fields(reports))]
+    pub fn new_from_input(input: &Input) -> anyhow::Result<Report> {
         let mut data = vec![];
-        for line in reader.lines() {
-            let s = line?;
-            let r = s
-                .split(' ')
-                .filter_map(|v| {
-                    if v.is_empty() {
-                        None
-                    } else {
-                        Some(v.parse::<i32>().unwrap_or(0))
-                    }
-                })
-                .collect::<Vec<i32>>();
+        for (i, s) in input.lines().enumerate() {
+            if s.is_empty() {
+                continue;
+            }
+
+            let r = ints(s, ' ')
+                .map_err(|v| InputError::malformed(i + 1, format!("`{v}` is not a level")))?;
 
             data.push(r);
         }
 
+        tracing::Span::current().record("reports", data.len());
+
         Ok(Report { data })
     }
 
@@ -54,33 +58,177 @@ impl Report {
         true
     }
 
+    fn is_dampened_safe(reports: &[i32]) -> bool {
+        if Self::is_safe(reports) {
+            return true;
+        }
+
+        for (k, _n) in reports.iter().enumerate() {
+            let mut p = reports.to_vec();
+            p.remove(k);
+
+            if Self::is_safe(&p) {
+                return true;
+            };
+        }
+
+        false
+    }
+
+    /// Index of the first pair of levels that breaks the rules, if any.
+    fn first_unsafe(levels: impl Iterator<Item = i32>) -> Option<usize> {
+        let mut levels = levels.peekable();
+        let mut level = None;
+        let mut i = 0;
+        while let (Some(a), Some(&b)) = (levels.next(), levels.peek()) {
+            let delta = b - a;
+            let current_level = Some(delta > 0);
+            if delta == 0 || delta.abs() > 3 || level.is_some_and(|l| Some(l) != current_level) {
+                return Some(i);
+            }
+
+            level = current_level;
+            i += 1;
+        }
+
+        None
+    }
+
+    /// Same as [`Self::is_dampened_safe`] without trying every level: only the
+    /// levels around the first bad pair can make the report safe when removed.
+    fn is_dampened_safe_fast(reports: &[i32]) -> bool {
+        let Some(i) = Self::first_unsafe(reports.iter().copied()) else {
+            return true;
+        };
+
+        [i.saturating_sub(1), i, i + 1].into_iter().any(|skip| {
+            let levels = reports
+                .iter()
+                .enumerate()
+                .filter(|&(k, _)| k != skip)
+                .map(|(_, v)| *v);
+
+            Self::first_unsafe(levels).is_none()
+        })
+    }
+
     pub fn sum_of_safe_reports(&self) -> i32 {
         self.data.iter().filter(|d| Self::is_safe(d)).count() as i32
     }
 
     pub fn sum_of_dampened_reports(&self) -> i32 {
+        Context::run_uncancelled(|ctx| self.sum_of_dampened_reports_with(ctx))
+    }
+
+    pub fn sum_of_dampened_reports_with(&self, ctx: &Context) -> anyhow::Result<i32> {
         let mut total = 0;
-        self.data.iter().for_each(|v| {
-            if Self::is_safe(v) {
+        for (i, v) in self.data.iter().enumerate() {
+            ctx.tick(i, self.data.len())?;
+
+            if Self::is_dampened_safe(v) {
                 total += 1;
-                return;
             }
+        }
+        ctx.progress(self.data.len(), self.data.len());
 
-            for (k, _n) in v.iter().enumerate() {
-                let mut p = v.clone();
-                p.remove(k);
+        Ok(total)
+    }
 
-                if Self::is_safe(&p) {
-                    total += 1;
-                    return;
-                };
+    pub fn sum_of_dampened_reports_fast(&self, ctx: &Context) -> anyhow::Result<i32> {
+        let mut total = 0;
+        for (i, v) in self.data.iter().enumerate() {
+            ctx.tick(i, self.data.len())?;
+
+            if Self::is_dampened_safe_fast(v) {
+                total += 1;
             }
-        });
+        }
+        ctx.progress(self.data.len(), self.data.len());
 
-        total
+        Ok(total)
     }
 }
 
+impl Solve for Report {
+    fn part_one(&self, _ctx: &Context) -> anyhow::Result<String> {
+        Ok(self.sum_of_safe_reports().to_string())
+    }
+
+    fn part_two(&self, ctx: &Context) -> anyhow::Result<String> {
+        Ok(self.sum_of_dampened_reports_with(ctx)?.to_string())
+    }
+
+    fn queries(&self) -> &'static [(&'static str, &'static str)] {
+        &[
+            ("count", "number of reports"),
+            (
+                "report <n>",
+                "levels of the nth report and whether it is safe",
+            ),
+        ]
+    }
+
+    fn query(&self, command: &str, args: &[&str]) -> Option<anyhow::Result<String>> {
+        match command {
+            "count" => Some(Ok(self.data.len().to_string())),
+            "report" => Some(arg::<usize>(args, 0).and_then(|n| {
+                let Some(levels) = self.data.get(n) else {
+                    anyhow::bail!("no report {n}, there are {}", self.data.len())
+                };
+
+                Ok(format!(
+                    "{levels:?} safe: {} dampened: {}",
+                    Self::is_safe(levels),
+                    Self::is_dampened_safe(levels)
+                ))
+            })),
+            _ => None,
+        }
+    }
+}
+
+/// `reports` lines of 5 to 8 levels, mostly safe or one level away from it.
+pub fn generate(rng: &mut Rng, reports: usize) -> String {
+    let mut out = String::new();
+    for _ in 0..reports {
+        let len = rng.range(5, 9) as usize;
+        push_report(rng, len, &mut out);
+    }
+
+    out
+}
+
+/// For `aoc scale`: 100 reports of about `size / 2` levels, so the time spent
+/// per report shows rather than the number of reports.
+pub fn generate_long(rng: &mut Rng, size: usize) -> String {
+    let mut out = String::new();
+    for _ in 0..100 {
+        let len = rng.range(5, 9) as usize + size / 2;
+        push_report(rng, len, &mut out);
+    }
+
+    out
+}
+
+fn push_report(rng: &mut Rng, len: usize, out: &mut String) {
+    let sign = if rng.chance(0.5) { 1 } else { -1 };
+    // decreasing reports start high enough to stay positive
+    let mut level = rng.range(30, 70) + if sign < 0 { 3 * len as i32 } else { 0 };
+    let mut levels = vec![];
+    for _ in 0..len {
+        levels.push(level);
+        level += sign * rng.range(1, 4);
+    }
+    if rng.chance(0.5) {
+        let i = rng.below(len);
+        levels[i] += rng.range(-4, 5);
+    }
+
+    let line = levels.iter().map(|l| l.to_string()).collect::<Vec<_>>();
+    out.push_str(&line.join(" "));
+    out.push('\n');
+}
+
 fn part_one() -> anyhow::Result<i32> {
     let report = Report::new_from_data()?;
 
@@ -93,9 +241,27 @@ fn part_two() -> anyhow::Result<i32> {
     Ok(report.sum_of_dampened_reports())
 }
 
+pub const BUDGET: Budget = Budget::millis(2, 1, 1);
+
 #[cfg(test)]
 mod test {
-    use super::{part_one, part_two};
+    use proptest::{collection::vec, prelude::*};
+
+    use super::{Report, part_one, part_two};
+    use crate::{
+        input::Input,
+        strategy::{join_spaced, number},
+    };
+
+    fn reports() -> impl Strategy<Value = (Vec<Vec<i32>>, String)> {
+        vec(vec(number(), 1..10), 0..50).prop_flat_map(|data| {
+            let lines = data
+                .iter()
+                .map(|r| join_spaced(r.iter().map(|v| v.to_string()).collect()))
+                .collect::<Vec<_>>();
+            (Just(data), lines.prop_map(|l| l.join("\n")))
+        })
+    }
 
     #[test]
     fn test_2_1() {
@@ -108,4 +274,43 @@ mod test {
         let ans = part_two().unwrap();
         assert_eq!(692, ans);
     }
+
+    proptest! {
+        #[test]
+        fn test_parse((data, input) in reports()) {
+            let report = Report::new_from_input(&Input::new(&input)).unwrap();
+
+            prop_assert_eq!(data, report.data);
+        }
+    }
+
+    fn render(data: &[Vec<i32>]) -> String {
+        data.iter()
+            .map(|r| {
+                r.iter()
+                    .map(|v| v.to_string())
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    proptest! {
+        #[test]
+        fn test_reversed_reports(data in vec(vec(0..15, 1..8), 0..40)) {
+            let reversed = data
+                .iter()
+                .map(|r| r.iter().rev().copied().collect())
+                .collect::<Vec<_>>();
+            let report = Report::new_from_input(&Input::new(&render(&data))).unwrap();
+            let mirrored = Report::new_from_input(&Input::new(&render(&reversed))).unwrap();
+
+            prop_assert_eq!(report.sum_of_safe_reports(), mirrored.sum_of_safe_reports());
+            prop_assert_eq!(
+                report.sum_of_dampened_reports(),
+                mirrored.sum_of_dampened_reports()
+            );
+        }
+    }
 }
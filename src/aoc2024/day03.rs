@@ -1,165 +1,253 @@
-use std::{borrow::Cow, io::BufRead};
+use std::borrow::Cow;
 
 use anyhow::Ok;
 
-use crate::read_input;
+use crate::{
+    budget::Budget,
+    combinator::{Parser, alt, digits, literal, map, pair, preceded, scan, terminated},
+    context::Context,
+    generate::Rng,
+    input::Input,
+    solution::Solve,
+};
 
-pub struct Computer {
-    raw: Cow<'static, str>,
+enum Instruction {
+    Mul(i32, i32),
+    Do,
+    Dont,
 }
 
-impl Computer {
+impl Instruction {
+    /// A complete `mul(X,Y)` with 1 to 3 digit operands, `do()` or `don't()`.
+    fn parser() -> impl Parser<Instruction> {
+        let operand = || digits(1, 3);
+        let mul = preceded(
+            literal("mul("),
+            terminated(
+                pair(terminated(operand(), literal(",")), operand()),
+                literal(")"),
+            ),
+        );
+
+        alt(
+            map(mul, |(n1, n2)| Instruction::Mul(n1, n2)),
+            alt(
+                map(literal("do()"), |_| Instruction::Do),
+                map(literal("don't()"), |_| Instruction::Dont),
+            ),
+        )
+    }
+}
+
+/// Corrupted memory, scanned as given so a line break just ends whatever
+/// instruction it interrupts.
+pub struct Computer<'a> {
+    raw: Cow<'a, str>,
+}
+
+impl Computer<'static> {
     pub fn new_from_data() -> anyhow::Result<Self> {
-        let reader = read_input("2024/3.txt")?;
-        let raw = reader.lines().collect::<Result<String, _>>()?;
-
-        Ok(Computer {
-            raw: Cow::from(raw),
-        })
-    }
-
-    fn find_mul(&self) -> anyhow::Result<Vec<(i32, i32)>> {
-        let mut nums = vec![];
-        let mut chars = self.raw.chars().peekable();
-        while let Some(ch) = chars.next() {
-            if ch == 'm' && chars.peek() == Some(&'u') {
-                chars.next();
-                if chars.peek() == Some(&'l') {
-                    chars.next();
-                    if chars.peek() == Some(&'(') {
-                        chars.next();
-
-                        let mut number1 = String::new();
-                        while let Some(digit) = chars.peek() {
-                            if digit.is_ascii_digit() {
-                                number1.push(*digit);
-                                chars.next();
-                            } else {
-                                break;
-                            }
-                        }
+        Ok(Self::from_text(Input::embedded("2024/3.txt")?.into_text()))
+    }
+}
 
-                        if chars.peek() == Some(&',') {
-                            chars.next();
-
-                            let mut number2 = String::new();
-                            while let Some(digit) = chars.peek() {
-                                if digit.is_ascii_digit() {
-                                    number2.push(*digit);
-                                    chars.next();
-                                } else {
-                                    break;
-                                }
-                            }
-
-                            if chars.peek() == Some(&')') {
-                                chars.next();
-                                nums.push((number1.parse::<i32>()?, number2.parse::<i32>()?));
-                            }
-                        }
-                    }
-                }
-            }
+impl<'a> Computer<'a> {
+    pub fn new_from_input(input: &'a Input) -> anyhow::Result<Self> {
+        Ok(Self::from_text(Cow::Borrowed(input.as_str())))
+    }
+
+    #[tracing::instrument(skip_all, fields(len))]
+    fn from_text(raw: Cow<'a, str>) -> Self {
+        tracing::Span::current().record("len", raw.len());
+
+        Computer { raw }
+    }
+
+    /// Copies borrowed memory so the computer can outlive its input.
+    pub fn into_owned(self) -> Computer<'static> {
+        Computer {
+            raw: Cow::Owned(self.raw.into_owned()),
         }
+    }
+
+    fn instructions(&self) -> impl Iterator<Item = Instruction> {
+        scan(&self.raw, Instruction::parser())
+    }
 
-        Ok(nums)
+    pub fn find_mul(&self) -> Vec<(i32, i32)> {
+        self.instructions()
+            .filter_map(|i| match i {
+                Instruction::Mul(n1, n2) => Some((n1, n2)),
+                _ => None,
+            })
+            .collect()
     }
 
-    fn find_mul_conditional(&self) -> anyhow::Result<Vec<(i32, i32)>> {
-        let mut nums = vec![];
-        let mut chars = self.raw.chars().peekable();
+    pub fn find_mul_conditional(&self) -> Vec<(i32, i32)> {
         let mut do_mul = true;
-        while let Some(ch) = chars.next() {
-            if ch == 'd' && chars.peek() == Some(&'o') {
-                chars.next();
-                if chars.peek() == Some(&'n') {
-                    chars.next();
-                    if chars.peek() == Some(&'\'') {
-                        chars.next();
-                        if chars.peek() == Some(&'t') {
-                            chars.next();
-                            do_mul = false;
-                        }
-                    }
-                } else {
+        self.instructions()
+            .filter_map(|i| match i {
+                Instruction::Mul(n1, n2) if do_mul => Some((n1, n2)),
+                Instruction::Mul(..) => None,
+                Instruction::Do => {
                     do_mul = true;
+                    None
                 }
-            } else if ch == 'm' && chars.peek() == Some(&'u') {
-                chars.next();
-                if !do_mul {
-                    continue;
-                }
-
-                if chars.peek() == Some(&'l') {
-                    chars.next();
-                    if chars.peek() == Some(&'(') {
-                        chars.next();
-
-                        let mut number1 = String::new();
-                        while let Some(digit) = chars.peek() {
-                            if digit.is_ascii_digit() {
-                                number1.push(*digit);
-                                chars.next();
-                            } else {
-                                break;
-                            }
-                        }
-
-                        if chars.peek() == Some(&',') {
-                            chars.next();
-
-                            let mut number2 = String::new();
-                            while let Some(digit) = chars.peek() {
-                                if digit.is_ascii_digit() {
-                                    number2.push(*digit);
-                                    chars.next();
-                                } else {
-                                    break;
-                                }
-                            }
-
-                            if chars.peek() == Some(&')') {
-                                chars.next();
-                                nums.push((number1.parse::<i32>()?, number2.parse::<i32>()?));
-                            }
-                        }
-                    }
+                Instruction::Dont => {
+                    do_mul = false;
+                    None
                 }
-            }
-        }
-
-        Ok(nums)
+            })
+            .collect()
     }
 
-    pub fn do_multiplication(&self) -> anyhow::Result<i32> {
-        let res = self.find_mul()?.iter().map(|(n1, n2)| (n1 * n2)).sum();
+    /// Summed in `i64`, about ten thousand products of 3 digit operands go
+    /// past `i32`.
+    pub fn do_multiplication(&self) -> anyhow::Result<i64> {
+        let res = self
+            .find_mul()
+            .iter()
+            .map(|(n1, n2)| i64::from(n1 * n2))
+            .sum();
         Ok(res)
     }
 
-    pub fn do_conditional_multiplication(&self) -> anyhow::Result<i32> {
+    pub fn do_conditional_multiplication(&self) -> anyhow::Result<i64> {
         let res = self
-            .find_mul_conditional()?
+            .find_mul_conditional()
             .iter()
-            .map(|(n1, n2)| (n1 * n2))
+            .map(|(n1, n2)| i64::from(n1 * n2))
             .sum();
         Ok(res)
     }
 }
 
-fn part_one() -> anyhow::Result<i32> {
+impl Solve for Computer<'_> {
+    fn part_one(&self, _ctx: &Context) -> anyhow::Result<String> {
+        Ok(self.do_multiplication()?.to_string())
+    }
+
+    fn part_two(&self, _ctx: &Context) -> anyhow::Result<String> {
+        Ok(self.do_conditional_multiplication()?.to_string())
+    }
+
+    fn queries(&self) -> &'static [(&'static str, &'static str)] {
+        &[
+            ("len", "length of the corrupted memory"),
+            ("muls", "number of mul instructions"),
+            (
+                "enabled",
+                "number of mul instructions enabled by do() and don't()",
+            ),
+        ]
+    }
+
+    fn query(&self, command: &str, _args: &[&str]) -> Option<anyhow::Result<String>> {
+        match command {
+            "len" => Some(Ok(self.raw.len().to_string())),
+            "muls" => Some(Ok(self.find_mul().len().to_string())),
+            "enabled" => Some(Ok(self.find_mul_conditional().len().to_string())),
+            _ => None,
+        }
+    }
+}
+
+/// About `len` bytes of corrupted memory in lines of up to 3000 bytes.
+pub fn generate(rng: &mut Rng, len: usize) -> String {
+    const NOISE: &[u8] = b"!@#$%^&*()[]{}<>,:;'? +-/0123456789mulodnt'why";
+
+    let mut out = String::new();
+    let mut line = 0;
+    while out.len() < len {
+        let segment = match rng.below(10) {
+            0..=3 => format!("mul({},{})", rng.range(0, 1000), rng.range(0, 1000)),
+            4 => "do()".to_string(),
+            5 => "don't()".to_string(),
+            6 => format!("mul({},{}]", rng.range(0, 1000), rng.range(0, 1000)),
+            _ => (0..rng.range(1, 8))
+                .map(|_| *rng.choose(NOISE) as char)
+                .collect(),
+        };
+
+        line += segment.len();
+        out.push_str(&segment);
+        if line > 3000 {
+            out.push('\n');
+            line = 0;
+        }
+    }
+
+    out
+}
+
+fn part_one() -> anyhow::Result<i64> {
     let computer = Computer::new_from_data()?;
     computer.do_multiplication()
 }
 
-fn part_two() -> anyhow::Result<i32> {
+fn part_two() -> anyhow::Result<i64> {
     let computer = Computer::new_from_data()?;
 
     computer.do_conditional_multiplication()
 }
 
+pub const BUDGET: Budget = Budget::millis(1, 1, 1);
+
 #[cfg(test)]
 mod test {
-    use super::{part_one, part_two};
+    use std::borrow::Cow;
+
+    use proptest::{collection::vec, prelude::*};
+
+    use super::{Computer, part_one, part_two};
+    use crate::input::Input;
+
+    #[derive(Debug, Clone)]
+    enum Segment {
+        Noise(String),
+        Mul(i32, i32),
+        Do,
+        Dont,
+    }
+
+    impl Segment {
+        fn render(&self) -> String {
+            match self {
+                Segment::Noise(s) => s.clone(),
+                Segment::Mul(a, b) => format!("mul({a},{b})"),
+                Segment::Do => "do()".to_string(),
+                Segment::Dont => "don't()".to_string(),
+            }
+        }
+    }
+
+    /// Corrupted memory that never completes a `mul`, `do` or `don't` on its own
+    /// or together with its neighbours.
+    fn noise() -> impl Strategy<Value = String> {
+        prop_oneof![
+            "[a-ce-lnot-zA-Z !?*<>{}#'\\[\\]]{1,8}",
+            Just("mul(1,2]".to_string()),
+            Just("mul[3,4]".to_string()),
+            Just("mul (2,3)".to_string()),
+            Just("mul(4*".to_string()),
+            Just("?(12,34)".to_string()),
+            Just("mul(6,9!".to_string()),
+            Just("mul(,5)".to_string()),
+            Just("mul(1234,5)".to_string()),
+        ]
+    }
+
+    fn memory() -> impl Strategy<Value = Vec<Segment>> {
+        vec(
+            prop_oneof![
+                noise().prop_map(Segment::Noise),
+                (0..1000, 0..1000).prop_map(|(a, b)| Segment::Mul(a, b)),
+                Just(Segment::Do),
+                Just(Segment::Dont),
+            ],
+            0..40,
+        )
+    }
 
     #[test]
     fn test_3_1() {
@@ -172,4 +260,52 @@ mod test {
         let ans = part_two().unwrap();
         assert_eq!(95411583, ans);
     }
+
+    #[test]
+    fn test_malformed_operands() {
+        let input = Input::new("mul(,5)mul(1234,5)mul(99999999999,1)mul(12,345)");
+        let computer = Computer::new_from_input(&input).unwrap();
+        assert_eq!(vec![(12, 345)], computer.find_mul());
+
+        let input = Input::new("dont()don't(mul(1,2)do(mul(3,4)");
+        let computer = Computer::new_from_input(&input).unwrap();
+        assert_eq!(vec![(1, 2), (3, 4)], computer.find_mul_conditional());
+    }
+
+    #[test]
+    fn test_line_breaks() {
+        let input = Input::new("mul(1,2)\nmul(3,\n4)do()\n");
+        let computer = Computer::new_from_input(&input).unwrap();
+        assert!(matches!(computer.raw, Cow::Borrowed(_)));
+        assert_eq!(vec![(1, 2)], computer.find_mul());
+    }
+
+    proptest! {
+        #[test]
+        fn test_find_mul(segments in memory()) {
+            let input = segments.iter().map(|s| s.render()).collect::<String>();
+            let input = Input::new(&input);
+            let computer = Computer::new_from_input(&input).unwrap();
+
+            let mut all = vec![];
+            let mut enabled = vec![];
+            let mut on = true;
+            for s in &segments {
+                match s {
+                    Segment::Mul(a, b) => {
+                        all.push((*a, *b));
+                        if on {
+                            enabled.push((*a, *b));
+                        }
+                    }
+                    Segment::Do => on = true,
+                    Segment::Dont => on = false,
+                    Segment::Noise(_) => {}
+                }
+            }
+
+            prop_assert_eq!(all, computer.find_mul());
+            prop_assert_eq!(enabled, computer.find_mul_conditional());
+        }
+    }
 }
@@ -1,248 +1,98 @@
-use std::{borrow::Cow, io::BufRead};
-
-use crate::read_input;
-
-#[derive(Clone, Copy)]
-enum Direction {
-    Right,
-    Left,
-    Up,
-    Down,
-    DiagUpRight,
-    DiagUpLeft,
-    DiagDownRight,
-    DiagDownLeft,
-}
-
-#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
-struct Pos(i32, i32);
-
-impl Pos {
-    // 0.0 is top left
-    fn matrix_path(
-        &self,
-        n: i32,
-        direction: Direction,
-        matrix_size: (i32, i32),
-    ) -> anyhow::Result<Vec<Self>> {
-        if n < 1 {
-            anyhow::bail!("negative is forbidden, use direction to move")
-        }
-
-        let mut path = vec![];
-
-        match direction {
-            Direction::Right => {
-                if self.0 + n > (matrix_size.0 - 1) {
-                    anyhow::bail!("out of bounds")
-                }
-
-                for i in 0..n {
-                    path.push(Pos(self.0 + i + 1, self.1));
-                }
-            }
-            Direction::Left => {
-                if self.0 - n < 0 {
-                    anyhow::bail!("out of bounds")
-                }
-
-                for i in 0..n {
-                    path.push(Pos(self.0 - (i + 1), self.1));
-                }
-            }
-            Direction::Up => {
-                if self.1 - n < 0 {
-                    anyhow::bail!("out of bounds")
-                }
-
-                for i in 0..n {
-                    path.push(Pos(self.0, self.1 - (i + 1)));
-                }
-            }
-            Direction::Down => {
-                if self.1 + n > (matrix_size.1 - 1) {
-                    anyhow::bail!("out of bounds")
-                }
-
-                for i in 0..n {
-                    path.push(Pos(self.0, self.1 + (i + 1)));
-                }
-            }
-            Direction::DiagUpLeft => {
-                for i in 0..n {
-                    let delta = i + 1;
-                    let x = self.0 - delta;
-                    let y = self.1 - delta;
-
-                    if x < 0 || y < 0 {
-                        anyhow::bail!("out of bounds")
-                    }
-
-                    path.push(Pos(x, y));
-                }
-            }
-            Direction::DiagUpRight => {
-                for i in 0..n {
-                    let delta = i + 1;
-                    let x = self.0 + delta;
-                    let y = self.1 - delta;
-
-                    if x > matrix_size.0 - 1 || y < 0 {
-                        anyhow::bail!("out of bounds")
-                    }
-
-                    path.push(Pos(x, y));
-                }
-            }
-            Direction::DiagDownLeft => {
-                for i in 0..n {
-                    let delta = i + 1;
-                    let x = self.0 - delta;
-                    let y = self.1 + delta;
-
-                    if x < 0 || y > matrix_size.1 - 1 {
-                        anyhow::bail!("out of bounds")
-                    }
-
-                    path.push(Pos(x, y));
-                }
-            }
-            Direction::DiagDownRight => {
-                for i in 0..n {
-                    let delta = i + 1;
-                    let x = self.0 + delta;
-                    let y = self.1 + delta;
-
-                    if x > matrix_size.0 - 1 || y > matrix_size.1 - 1 {
-                        anyhow::bail!("out of bounds")
-                    }
-
-                    path.push(Pos(x, y));
-                }
-            }
-        }
+use crate::{
+    budget::Budget,
+    context::Context,
+    generate::Rng,
+    geometry::{Direction, Pos},
+    grid::Grid,
+    input::Input,
+    solution::{Solve, arg},
+    visualize::{Frame, Highlight, Visualize},
+};
+
+/// The `n` positions after `start` going `direction`, all inside `grid`.
+fn path<T>(start: Pos, n: i32, direction: Direction, grid: &Grid<T>) -> anyhow::Result<Vec<Pos>> {
+    if n < 1 {
+        anyhow::bail!("negative is forbidden, use direction to move")
+    }
 
-        Ok(path)
+    let path = start.ray(direction).take(n as usize).collect::<Vec<_>>();
+    if !path.iter().all(|p| grid.contains(*p)) {
+        anyhow::bail!("out of bounds")
     }
+
+    Ok(path)
 }
 
 #[derive(Default)]
 pub struct ElfMonitor {
-    matrix: Vec<Vec<Cow<'static, str>>>,
+    matrix: Grid<char>,
 }
 
 impl ElfMonitor {
     pub fn new_from_data() -> anyhow::Result<Self> {
-        let reader = read_input("2024/4.txt")?;
+        Self::new_from_input(&Input::embedded("2024/4.txt")?)
+    }
+
+    #[tracing::instrument(skip_all, fields(rows, columns))]
+    pub fn new_from_input(input: &Input) -> anyhow::Result<Self> {
+        let matrix = input.grid()?;
 
-        let matrix: Vec<Vec<Cow<'static, str>>> = reader
-            .lines()
-            .map(|line| line.map(|l| l.chars().map(|c| Cow::from(c.to_string())).collect()))
-            .collect::<Result<_, _>>()?;
+        let span = tracing::Span::current();
+        span.record("rows", matrix.height());
+        span.record("columns", matrix.width());
 
         Ok(Self { matrix })
     }
 
     fn find_mas(&self, start: Pos) -> usize {
-        let paths = self.mas_paths_in_all_direction(start);
-        paths
-            .iter()
-            .filter(|p| self.matrix_string(p) == "MAS")
-            .count()
+        self.mas_matches(start).len()
     }
 
-    fn matrix_string(&self, paths: &[Pos]) -> Cow<'static, str> {
-        paths
+    fn mas_matches(&self, start: Pos) -> Vec<Vec<Pos>> {
+        Direction::ALL
             .iter()
-            .map(|p| {
-                let x = p.0 as usize;
-                let y = p.1 as usize;
-
-                self.matrix[x][y].chars().as_str()
-            })
+            .filter_map(|dir| path(start, 3, *dir, &self.matrix).ok())
+            .filter(|p| p.iter().map(|p| self.matrix[*p]).eq("MAS".chars()))
             .collect()
     }
 
-    fn mas_paths_in_all_direction(&self, start: Pos) -> Vec<Vec<Pos>> {
-        let size = (self.matrix[0].len() as i32, self.matrix.len() as i32);
-        [
-            Direction::Right,
-            Direction::Left,
-            Direction::Up,
-            Direction::Down,
-            Direction::DiagUpLeft,
-            Direction::DiagUpRight,
-            Direction::DiagDownLeft,
-            Direction::DiagDownRight,
-        ]
-        .iter()
-        .filter_map(|dir| {
-            if let Ok(path) = start.matrix_path(3, *dir, size) {
-                Some(path)
-            } else {
-                None
-            }
-        })
-        .collect()
+    pub fn count_xmas(&self) -> i32 {
+        Context::run_uncancelled(|ctx| self.count_xmas_with(ctx))
     }
 
-    pub fn count_xmas(&self) -> i32 {
+    pub fn count_xmas_with(&self, ctx: &Context) -> anyhow::Result<i32> {
         let mut total = 0;
-        let mut pos = Pos(0, 0);
-
-        let max_down = self.matrix.len() as i32 - 1;
-        let max_right = self.matrix[0].len() as i32 - 1;
-
-        while pos.0 <= max_down && pos.1 <= max_right {
-            let c = &self.matrix[pos.0 as usize][pos.1 as usize];
-            if c == "X" {
-                total += self.find_mas(pos);
-            }
-
-            if pos.1 < max_right {
-                pos.1 += 1;
-            } else {
-                pos.1 = 0;
-                pos.0 += 1;
+        for y in 0..self.matrix.height() {
+            for x in 0..self.matrix.width() {
+                let pos = Pos::new(x as i32, y as i32);
+                if self.matrix[pos] == 'X' {
+                    total += self.find_mas(pos);
+                }
             }
+            ctx.tick(y + 1, self.matrix.height())?;
         }
 
-        total as i32
+        Ok(total as i32)
     }
 
     pub fn count_crossmas(&self) -> i32 {
-        let mut total = 0;
-        let mut pos = (1, 1);
-
-        let max_down = self.matrix.len() - 2;
-        let max_right = self.matrix[0].len() - 2;
-
-        while pos.0 <= max_down && pos.1 <= max_right {
-            let c = &self.matrix[pos.0][pos.1];
-            if c == "A" {
-                let top_left = &self.matrix[pos.0 - 1][pos.1 - 1];
-                let bottom_right = &self.matrix[pos.0 + 1][pos.1 + 1];
-                let top_right = &self.matrix[pos.0 - 1][pos.1 + 1];
-                let bottom_left = &self.matrix[pos.0 + 1][pos.1 - 1];
-
-                if Self::valid_cross(top_left, top_right, bottom_left, bottom_right) {
-                    total += 1;
-                }
-            }
-
-            if pos.1 < max_right {
-                pos.1 += 1;
-            } else {
-                pos.1 = 1;
-                pos.0 += 1;
-            }
-        }
-
-        total
+        self.matrix
+            .find_all(&'A')
+            .filter(|p| {
+                let corner = |dx, dy| self.matrix.get(Pos::new(p.x + dx, p.y + dy)).copied();
+                let (Some(top_left), Some(top_right), Some(bottom_left), Some(bottom_right)) =
+                    (corner(-1, -1), corner(1, -1), corner(-1, 1), corner(1, 1))
+                else {
+                    return false;
+                };
+
+                Self::valid_cross(top_left, top_right, bottom_left, bottom_right)
+            })
+            .count() as i32
     }
 
-    fn valid_cross(top_left: &str, top_right: &str, bottom_left: &str, bottom_right: &str) -> bool {
-        let valid_char = |s| matches!(s, "M" | "S");
+    fn valid_cross(top_left: char, top_right: char, bottom_left: char, bottom_right: char) -> bool {
+        let valid_char = |c| matches!(c, 'M' | 'S');
 
         if !valid_char(top_left)
             || !valid_char(top_right)
@@ -260,9 +110,120 @@ impl ElfMonitor {
     }
 }
 
+impl Visualize for ElfMonitor {
+    /// One frame per scanned row, with every XMAS found so far highlighted.
+    fn frames(&self) -> Box<dyn Iterator<Item = Frame> + '_> {
+        let grid = self.matrix.rows().map(<[char]>::to_vec).collect::<Vec<_>>();
+        let mut matches = vec![];
+        let mut total = 0;
+
+        Box::new((0..self.matrix.height()).map(move |row| {
+            for col in 0..self.matrix.width() {
+                let start = Pos::new(col as i32, row as i32);
+                if self.matrix[start] != 'X' {
+                    continue;
+                }
+
+                for path in self.mas_matches(start) {
+                    total += 1;
+                    for p in std::iter::once(start).chain(path) {
+                        matches.push((p.y as usize, p.x as usize, Highlight::Match));
+                    }
+                }
+            }
+
+            let mut frame = Frame::new(grid.clone());
+            frame.highlights = (0..self.matrix.width())
+                .map(|col| (row, col, Highlight::Active))
+                .chain(matches.iter().cloned())
+                .collect();
+            frame.annotation = format!("row {row}: {total} XMAS so far");
+
+            frame
+        }))
+    }
+}
+
+impl Solve for ElfMonitor {
+    fn part_one(&self, ctx: &Context) -> anyhow::Result<String> {
+        Ok(self.count_xmas_with(ctx)?.to_string())
+    }
+
+    fn part_two(&self, _ctx: &Context) -> anyhow::Result<String> {
+        Ok(self.count_crossmas().to_string())
+    }
+
+    fn queries(&self) -> &'static [(&'static str, &'static str)] {
+        &[
+            ("size", "rows and columns of the word search"),
+            ("cell <row> <col>", "letter at a position"),
+            ("xmas <row> <col>", "number of XMAS starting at a position"),
+        ]
+    }
+
+    fn visualize(&self) -> Option<&dyn Visualize> {
+        Some(self)
+    }
+
+    fn query(&self, command: &str, args: &[&str]) -> Option<anyhow::Result<String>> {
+        let cell = || -> anyhow::Result<Pos> {
+            let (row, col) = (arg::<i32>(args, 0)?, arg::<i32>(args, 1)?);
+            let pos = Pos::new(col, row);
+            if !self.matrix.contains(pos) {
+                anyhow::bail!("{row} {col} is out of bounds")
+            }
+
+            Ok(pos)
+        };
+
+        match command {
+            "size" => Some(Ok(format!(
+                "{} {}",
+                self.matrix.height(),
+                self.matrix.width()
+            ))),
+            "cell" => Some(cell().map(|p| self.matrix[p].to_string())),
+            "xmas" => Some(cell().map(|p| {
+                if self.matrix[p] == 'X' {
+                    self.find_mas(p)
+                } else {
+                    0
+                }
+                .to_string()
+            })),
+            _ => None,
+        }
+    }
+}
+
+/// A `side` x `side` word search of random XMAS letters.
+pub fn generate(rng: &mut Rng, side: usize) -> String {
+    let mut out = String::with_capacity(side * (side + 1));
+    for _ in 0..side {
+        for _ in 0..side {
+            out.push(*rng.choose(&['X', 'M', 'A', 'S']));
+        }
+        out.push('\n');
+    }
+
+    out
+}
+
+// count_xmas walks all eight directions from every `X`
+pub const BUDGET: Budget = Budget::millis(10, 40, 2);
+
 #[cfg(test)]
 mod test {
+    use proptest::{collection::vec, prelude::*, string::string_regex};
+
     use super::*;
+    use crate::context::Cancelled;
+
+    fn word_search() -> impl Strategy<Value = Vec<String>> {
+        (1..20usize, 1..20usize).prop_flat_map(|(rows, cols)| {
+            vec(string_regex(&format!("[XMAS]{{{cols}}}")).unwrap(), rows)
+        })
+    }
 
     #[test]
     fn test_4_1() {
@@ -280,10 +241,29 @@ mod test {
         assert_eq!(1902, total);
     }
 
+    #[test]
+    fn test_4_1_cancelled() {
+        let monitor = ElfMonitor::new_from_data().unwrap();
+        let ctx = Context::default();
+        ctx.cancel();
+
+        let err = monitor.count_xmas_with(&ctx).unwrap_err();
+        assert!(err.is::<Cancelled>());
+    }
+
+    #[test]
+    fn test_frames() {
+        let monitor = ElfMonitor::new_from_data().unwrap();
+
+        let frames = monitor.frames().collect::<Vec<_>>();
+        assert_eq!(140, frames.len());
+        assert_eq!("row 139: 2562 XMAS so far", frames[139].annotation);
+    }
+
     #[test]
     fn test_path() {
-        let pos = Pos(5, 5);
-        let size = (10, 10);
+        let pos = Pos::new(5, 5);
+        let grid = Grid::new(10, 10, '.');
 
         struct Tc {
             n: i32,
@@ -295,50 +275,97 @@ mod test {
             Tc {
                 n: 1,
                 dir: Direction::Right,
-                expected: vec![Pos(6, 5)],
+                expected: vec![Pos::new(6, 5)],
                 msg: "right",
             },
             Tc {
                 n: 1,
                 dir: Direction::Up,
-                expected: vec![Pos(5, 4)],
+                expected: vec![Pos::new(5, 4)],
                 msg: "top",
             },
             Tc {
                 n: 4,
                 dir: Direction::Down,
-                expected: vec![Pos(5, 6), Pos(5, 7), Pos(5, 8), Pos(5, 9)],
+                expected: vec![
+                    Pos::new(5, 6),
+                    Pos::new(5, 7),
+                    Pos::new(5, 8),
+                    Pos::new(5, 9),
+                ],
                 msg: "down",
             },
             Tc {
                 n: 2,
-                dir: Direction::DiagUpLeft,
-                expected: vec![Pos(4, 4), Pos(3, 3)],
+                dir: Direction::UpLeft,
+                expected: vec![Pos::new(4, 4), Pos::new(3, 3)],
                 msg: "diagonal top left",
             },
             Tc {
                 n: 2,
-                dir: Direction::DiagUpRight,
-                expected: vec![Pos(6, 4), Pos(7, 3)],
+                dir: Direction::UpRight,
+                expected: vec![Pos::new(6, 4), Pos::new(7, 3)],
                 msg: "diagonal top right",
             },
             Tc {
                 n: 2,
-                dir: Direction::DiagDownLeft,
-                expected: vec![Pos(4, 6), Pos(3, 7)],
+                dir: Direction::DownLeft,
+                expected: vec![Pos::new(4, 6), Pos::new(3, 7)],
                 msg: "diagonal bottom left",
             },
             Tc {
                 n: 2,
-                dir: Direction::DiagDownRight,
-                expected: vec![Pos(6, 6), Pos(7, 7)],
+                dir: Direction::DownRight,
+                expected: vec![Pos::new(6, 6), Pos::new(7, 7)],
                 msg: "diagonal bottom right",
             },
         ];
 
         for t in tcs {
-            let actual = pos.matrix_path(t.n, t.dir, size).unwrap();
+            let actual = path(pos, t.n, t.dir, &grid).unwrap();
             assert_eq!(t.expected, actual, "{}", t.msg);
         }
     }
+
+    proptest! {
+        #[test]
+        fn test_parse(rows in word_search()) {
+            let input = rows.join("\n");
+            let monitor = ElfMonitor::new_from_input(&Input::new(&input)).unwrap();
+
+            let parsed = monitor
+                .matrix
+                .rows()
+                .map(|r| r.iter().collect::<String>())
+                .collect::<Vec<_>>();
+            prop_assert_eq!(rows, parsed);
+        }
+    }
+
+    fn grid() -> impl Strategy<Value = Grid<char>> {
+        (3..12usize, 3..12usize).prop_flat_map(|(rows, cols)| {
+            vec(
+                prop_oneof![Just('X'), Just('M'), Just('A'), Just('S')],
+                rows * cols,
+            )
+            .prop_map(move |cells| Grid::from_vec(cols, cells))
+        })
+    }
+
+    fn counts(grid: &Grid<char>) -> (i32, i32) {
+        let monitor = ElfMonitor::new_from_input(&Input::new(&grid.to_string())).unwrap();
+
+        (monitor.count_xmas(), monitor.count_crossmas())
+    }
+
+    proptest! {
+        #[test]
+        fn test_symmetries(grid in grid()) {
+            let expected = counts(&grid);
+            prop_assert_eq!(expected, counts(&grid.transpose()));
+            prop_assert_eq!(expected, counts(&grid.flip_horizontal()));
+            prop_assert_eq!(expected, counts(&grid.flip_vertical()));
+            prop_assert_eq!(expected, counts(&grid.rotate_right()));
+        }
+    }
 }
@@ -1,9 +1,20 @@
-use std::{
-    collections::{HashMap, HashSet, VecDeque},
-    io::BufRead,
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use crate::{
+    budget::Budget,
+    context::Context,
+    generate::Rng,
+    input::{FromLine, Input, InputError, ints},
+    solution::{Solve, arg},
 };
 
-use crate::read_input;
+/// Page `before` has to come before page `after` when both are in an update.
+#[derive(FromLine)]
+#[line("{before}|{after}")]
+struct Rule {
+    before: i32,
+    after: i32,
+}
 
 #[derive(Debug)]
 pub struct ElfPrinter {
@@ -13,30 +24,55 @@ pub struct ElfPrinter {
 
 impl ElfPrinter {
     pub fn new_from_data() -> anyhow::Result<Self> {
-        let reader = read_input("2024/5.txt")?;
+        Self::new_from_input(&Input::embedded("2024/5.txt")?)
+    }
 
+    #[tracing::instrument(skip_all, fields(rules, updates))]
+    pub fn new_from_input(input: &Input) -> anyhow::Result<Self> {
         let mut rules: HashMap<i32, Vec<i32>> = HashMap::new();
         let mut updates: Vec<Vec<i32>> = vec![];
-        for line in reader.lines() {
-            let txt = line?;
-            if txt.contains('|') {
-                let nums = txt
-                    .split('|')
-                    .map(|x| x.parse::<i32>().unwrap_or(0))
-                    .collect::<Vec<_>>();
-                let before = nums.first().unwrap_or(&0);
-                let after = nums.last().unwrap_or(&0);
-
-                rules.entry(*before).or_default().push(*after);
-            } else if txt.contains(',') {
-                updates.push(
-                    txt.split(',')
-                        .map(|x| x.parse::<i32>().unwrap_or(0))
-                        .collect::<Vec<_>>(),
-                );
+        // only commas separate pages, `1|2` is a rule in the wrong place
+        let pages = |line: usize, txt: &str| {
+            ints(txt, ',').map_err(|x| match x {
+                "" => InputError::malformed(line, "missing a page between commas"),
+                x => InputError::malformed(line, format!("`{x}` is not a page")),
+            })
+        };
+
+        // rules come first, so a manual without any starts with a blank line
+        let mut sections = input.sections().peekable();
+        if let Some((first, section)) = sections.next_if(|(first, _)| *first == 0) {
+            for (i, txt) in section.lines().enumerate() {
+                let Rule { before, after } = Rule::from_line(txt)
+                    .map_err(|reason| InputError::malformed(first + i + 1, reason))?;
+
+                rules.entry(before).or_default().push(after);
             }
         }
 
+        if let Some((first, section)) = sections.next() {
+            for (i, txt) in section.lines().enumerate() {
+                let update = pages(first + i + 1, txt)?;
+                if update.len() % 2 == 0 {
+                    return Err(InputError::EvenUpdate {
+                        line: first + i + 1,
+                        pages: update.len(),
+                    }
+                    .into());
+                }
+
+                updates.push(update);
+            }
+        }
+
+        if let Some((first, _)) = sections.next() {
+            return Err(InputError::malformed(first + 1, "expected only rules and updates").into());
+        }
+
+        let span = tracing::Span::current();
+        span.record("rules", rules.values().map(|r| r.len()).sum::<usize>());
+        span.record("updates", updates.len());
+
         Ok(Self { rules, updates })
     }
 
@@ -44,10 +80,10 @@ impl ElfPrinter {
         let mut visited = HashSet::new();
         for page in update {
             for prev in visited.iter() {
-                if let Some(after) = &self.rules.get(prev) {
-                    if !after.iter().any(|a| a == page) {
-                        return false;
-                    }
+                if let Some(after) = &self.rules.get(prev)
+                    && !after.iter().any(|a| a == page)
+                {
+                    return false;
                 }
             }
 
@@ -62,14 +98,16 @@ impl ElfPrinter {
             .iter()
             .filter(|u| self.is_sorted(u))
             .map(|u| {
-                let middle = ((u.len() + 1) / 2) - 1;
+                let middle = u.len().div_ceil(2) - 1;
 
                 u.get(middle).unwrap_or(&0)
             })
             .sum()
     }
 
-    fn topological_sort(&self, update: &[i32]) -> Vec<i32> {
+    /// The pages of `update` in the order the rules give, or the pages whose
+    /// rules form a cycle.
+    fn topological_sort(&self, update: &[i32]) -> Result<Vec<i32>, InputError> {
         let mut graph: HashMap<&i32, Vec<i32>> = HashMap::new();
         let mut level_tracker: HashMap<&i32, usize> = HashMap::new();
 
@@ -110,27 +148,181 @@ impl ElfPrinter {
             }
         }
 
-        sorted_order
+        if sorted_order.len() != update.len() {
+            let mut pages = update
+                .iter()
+                .filter(|p| !sorted_order.contains(p))
+                .copied()
+                .collect::<Vec<_>>();
+            pages.sort();
+
+            return Err(InputError::CyclicRules { pages });
+        }
+
+        Ok(sorted_order)
     }
 
     pub fn sum_of_after_topological_sort(&self) -> i32 {
+        Context::run_uncancelled(|ctx| self.sum_of_after_topological_sort_with(ctx))
+    }
+
+    pub fn sum_of_after_topological_sort_with(&self, ctx: &Context) -> anyhow::Result<i32> {
         let mut total = 0;
-        self.updates
-            .iter()
-            .filter(|u| !self.is_sorted(u))
-            .for_each(|u| {
-                let sorted = self.topological_sort(u);
-                let middle = ((sorted.len() + 1) / 2) - 1;
-                total += sorted.get(middle).unwrap_or(&0);
-            });
+        for (i, u) in self.updates.iter().enumerate() {
+            ctx.tick(i, self.updates.len())?;
+            if self.is_sorted(u) {
+                continue;
+            }
+
+            let sorted = self.topological_sort(u)?;
+            let middle = sorted.len().div_ceil(2) - 1;
+            total += sorted.get(middle).unwrap_or(&0);
+        }
+        ctx.progress(self.updates.len(), self.updates.len());
+
+        Ok(total)
+    }
+}
+
+impl Solve for ElfPrinter {
+    fn part_one(&self, _ctx: &Context) -> anyhow::Result<String> {
+        Ok(self.sum_of_correct_ordered().to_string())
+    }
+
+    fn part_two(&self, ctx: &Context) -> anyhow::Result<String> {
+        Ok(self.sum_of_after_topological_sort_with(ctx)?.to_string())
+    }
+
+    fn queries(&self) -> &'static [(&'static str, &'static str)] {
+        &[
+            ("count", "number of updates"),
+            ("rules <page>", "pages that must be printed after a page"),
+            (
+                "update <n>",
+                "pages of the nth update and whether they are ordered",
+            ),
+        ]
+    }
+
+    fn query(&self, command: &str, args: &[&str]) -> Option<anyhow::Result<String>> {
+        match command {
+            "count" => Some(Ok(self.updates.len().to_string())),
+            "rules" => Some(arg::<i32>(args, 0).map(|page| {
+                let mut after = self.rules.get(&page).cloned().unwrap_or_default();
+                after.sort();
+                format!("{after:?}")
+            })),
+            "update" => Some(arg::<usize>(args, 0).and_then(|n| {
+                let Some(update) = self.updates.get(n) else {
+                    anyhow::bail!("no update {n}, there are {}", self.updates.len())
+                };
+
+                if self.is_sorted(update) {
+                    Ok(format!("{update:?} ordered"))
+                } else {
+                    Ok(format!(
+                        "{update:?} sorted: {:?}",
+                        self.topological_sort(update)?
+                    ))
+                }
+            })),
+            _ => None,
+        }
+    }
+}
 
-        total
+/// Rules over `pages` pages, each ordered against the next 24 like the real
+/// input, followed by `pages` updates of consecutive pages, half of them shuffled.
+pub fn generate(rng: &mut Rng, pages: usize) -> String {
+    manual(rng, pages.max(3), 24, pages)
+}
+
+/// For `aoc scale`: like [`generate`] with `size` pages, but rules reach one
+/// page further for every 50 of `size` and updates get as long as the rules
+/// reach, capped at 200 of them so their length is what grows.
+pub fn generate_long(rng: &mut Rng, size: usize) -> String {
+    let pages = size.max(3);
+    manual(rng, pages, 24 + size / 50, pages.min(200))
+}
+
+/// Rules ordering each page against the next `reach` ones, and `updates` runs
+/// of consecutive pages that the rules fully order.
+fn manual(rng: &mut Rng, pages: usize, reach: usize, updates: usize) -> String {
+    let mut order = (10..10 + pages as i32).collect::<Vec<_>>();
+    rng.shuffle(&mut order);
+
+    let mut rules = vec![];
+    for (i, before) in order.iter().enumerate() {
+        for after in order.iter().skip(i + 1).take(reach) {
+            rules.push(format!("{before}|{after}"));
+        }
     }
+    rng.shuffle(&mut rules);
+
+    let mut lines = vec![];
+    for _ in 0..updates {
+        // odd lengths so every update has a middle page
+        let longest = pages - 1 + pages % 2;
+        let len = (rng.range(1, reach as i32 / 2) as usize * 2 + 1).min(longest);
+        let start = rng.below(pages - len + 1);
+        let mut update = order[start..start + len].to_vec();
+        if rng.chance(0.5) {
+            rng.shuffle(&mut update);
+        }
+
+        lines.push(
+            update
+                .iter()
+                .map(|p| p.to_string())
+                .collect::<Vec<_>>()
+                .join(","),
+        );
+    }
+
+    format!("{}\n\n{}\n", rules.join("\n"), lines.join("\n"))
 }
 
+pub const BUDGET: Budget = Budget::millis(3, 3, 10);
+
 #[cfg(test)]
 mod test {
+    use std::collections::HashMap;
+
+    use proptest::{collection::vec, prelude::*, sample::subsequence};
+
     use super::ElfPrinter;
+    use crate::{
+        input::Input,
+        strategy::{join_padded, number},
+    };
+
+    type Model = (Vec<(i32, i32)>, Vec<Vec<i32>>);
+
+    fn manual() -> impl Strategy<Value = (Model, String)> {
+        (
+            vec((number(), number()), 0..40),
+            vec(
+                (0..5usize).prop_flat_map(|k| vec(number(), 2 * k + 1)),
+                0..20,
+            ),
+        )
+            .prop_flat_map(|(rules, updates)| {
+                let rule_lines = rules
+                    .iter()
+                    .map(|(a, b)| join_padded(vec![a.to_string(), b.to_string()], "|"))
+                    .collect::<Vec<_>>();
+                let update_lines = updates
+                    .iter()
+                    .map(|u| join_padded(u.iter().map(|v| v.to_string()).collect(), ","))
+                    .collect::<Vec<_>>();
+
+                (
+                    Just((rules, updates)),
+                    (rule_lines, update_lines)
+                        .prop_map(|(r, u)| format!("{}\n\n{}\n", r.join("\n"), u.join("\n"))),
+                )
+            })
+    }
 
     #[test]
     fn test_5_1() {
@@ -145,4 +337,74 @@ mod test {
 
         assert_eq!(6456, printer.sum_of_after_topological_sort())
     }
+
+    proptest! {
+        #[test]
+        fn test_parse(((rules, updates), input) in manual()) {
+            let printer = ElfPrinter::new_from_input(&Input::new(&input)).unwrap();
+
+            let mut expected: HashMap<i32, Vec<i32>> = HashMap::new();
+            for (before, after) in rules {
+                expected.entry(before).or_default().push(after);
+            }
+
+            prop_assert_eq!(expected, printer.rules);
+            prop_assert_eq!(updates, printer.updates);
+        }
+    }
+
+    /// Rules ordering every pair of a random set of pages, with updates drawn
+    /// from those pages.
+    fn ordered_manual() -> impl Strategy<Value = Model> {
+        subsequence((10..100).collect::<Vec<i32>>(), 3..20)
+            .prop_shuffle()
+            .prop_flat_map(|order| {
+                let rules = order
+                    .iter()
+                    .enumerate()
+                    .flat_map(|(i, a)| order[i + 1..].iter().map(move |b| (*a, *b)))
+                    .collect::<Vec<_>>();
+                let update = subsequence(order.clone(), 1..=order.len())
+                    .prop_filter("an update needs a middle page", |u| u.len() % 2 == 1)
+                    .prop_shuffle();
+
+                (Just(rules), vec(update, 1..10))
+            })
+    }
+
+    fn answers((rules, updates): &Model) -> (i32, i32) {
+        let rules = rules
+            .iter()
+            .map(|(a, b)| format!("{a}|{b}"))
+            .collect::<Vec<_>>();
+        let updates = updates
+            .iter()
+            .map(|u| {
+                u.iter()
+                    .map(|p| p.to_string())
+                    .collect::<Vec<_>>()
+                    .join(",")
+            })
+            .collect::<Vec<_>>();
+        let input = format!("{}\n\n{}\n", rules.join("\n"), updates.join("\n"));
+        let printer = ElfPrinter::new_from_input(&Input::new(&input)).unwrap();
+
+        (
+            printer.sum_of_correct_ordered(),
+            printer.sum_of_after_topological_sort(),
+        )
+    }
+
+    proptest! {
+        #[test]
+        fn test_shuffled_rules(
+            ((rules, updates), shuffled) in ordered_manual()
+                .prop_flat_map(|(rules, updates)| {
+                    ((Just(rules.clone()), Just(updates)), Just(rules).prop_shuffle())
+                })
+        ) {
+            let expected = answers(&(rules, updates.clone()));
+            prop_assert_eq!(expected, answers(&(shuffled, updates)));
+        }
+    }
 }
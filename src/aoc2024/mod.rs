@@ -1,5 +1,75 @@
+use self::{day01::Location, day02::Report, day03::Computer, day04::ElfMonitor, day05::ElfPrinter};
+use crate::{
+    input::Input,
+    solution::{Solution, Variant, parse},
+};
+
 pub mod day01;
 pub mod day02;
 pub mod day03;
 pub mod day04;
 pub mod day05;
+
+pub const SOLUTIONS: &[Solution] = &[
+    Solution {
+        year: 2024,
+        day: 1,
+        parse: || Ok(Box::new(Location::new_from_data()?)),
+        parse_input: |input| parse(input, Location::new_from_input),
+        generate: day01::generate,
+        scale: None,
+        budget: day01::BUDGET,
+        variants: &[],
+    },
+    Solution {
+        year: 2024,
+        day: 2,
+        parse: || Ok(Box::new(Report::new_from_data()?)),
+        parse_input: |input| parse(input, Report::new_from_input),
+        generate: day02::generate,
+        scale: Some(day02::generate_long),
+        budget: day02::BUDGET,
+        variants: &[Variant {
+            name: "single_pass_dampener",
+            part: 2,
+            solve: |input, ctx| {
+                let report = Report::new_from_input(&Input::new(input))?;
+                Ok(report.sum_of_dampened_reports_fast(ctx)?.to_string())
+            },
+        }],
+    },
+    Solution {
+        year: 2024,
+        day: 3,
+        parse: || Ok(Box::new(Computer::new_from_data()?)),
+        parse_input: |input| {
+            parse(input, |input| {
+                Ok(Computer::new_from_input(input)?.into_owned())
+            })
+        },
+        generate: day03::generate,
+        scale: None,
+        budget: day03::BUDGET,
+        variants: &[],
+    },
+    Solution {
+        year: 2024,
+        day: 4,
+        parse: || Ok(Box::new(ElfMonitor::new_from_data()?)),
+        parse_input: |input| parse(input, ElfMonitor::new_from_input),
+        generate: day04::generate,
+        scale: None,
+        budget: day04::BUDGET,
+        variants: &[],
+    },
+    Solution {
+        year: 2024,
+        day: 5,
+        parse: || Ok(Box::new(ElfPrinter::new_from_data()?)),
+        parse_input: |input| parse(input, ElfPrinter::new_from_input),
+        generate: day05::generate,
+        scale: Some(day05::generate_long),
+        budget: day05::BUDGET,
+        variants: &[],
+    },
+];
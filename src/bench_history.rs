@@ -0,0 +1,218 @@
+use std::{
+    collections::BTreeMap,
+    fs::{self, OpenOptions},
+    io::{BufRead, BufReader, Write},
+    path::Path,
+    process::Command,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use serde::{Deserialize, Serialize};
+
+/// One recorded `cargo bench` run, keyed by criterion benchmark id
+/// (e.g. `aoc2024_01/sum_of_difference`) with the median time in nanoseconds.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Entry {
+    pub commit: String,
+    pub timestamp: u64,
+    pub results: BTreeMap<String, f64>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Comparison {
+    pub id: String,
+    pub baseline: f64,
+    pub current: f64,
+}
+
+impl Comparison {
+    /// Relative change in percent, positive means slower. A baseline that
+    /// isn't positive can't be compared against and counts as no change.
+    pub fn change(&self) -> f64 {
+        if self.baseline <= 0.0 || !self.baseline.is_finite() {
+            return 0.0;
+        }
+
+        (self.current - self.baseline) / self.baseline * 100.0
+    }
+
+    pub fn is_regression(&self, threshold: f64) -> bool {
+        self.change() > threshold
+    }
+}
+
+#[derive(Deserialize)]
+struct Benchmark {
+    full_id: String,
+}
+
+#[derive(Deserialize)]
+struct Estimate {
+    point_estimate: f64,
+}
+
+#[derive(Deserialize)]
+struct Estimates {
+    median: Estimate,
+}
+
+impl Entry {
+    pub fn new(commit: String, results: BTreeMap<String, f64>) -> Self {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+
+        Self {
+            commit,
+            timestamp,
+            results,
+        }
+    }
+}
+
+/// Collects the latest median of every benchmark under criterion's output directory.
+pub fn collect(criterion_dir: &Path) -> anyhow::Result<BTreeMap<String, f64>> {
+    let mut results = BTreeMap::new();
+    if !criterion_dir.exists() {
+        anyhow::bail!(
+            "{} not found, run `cargo bench` first",
+            criterion_dir.display()
+        )
+    }
+
+    visit(criterion_dir, &mut results)?;
+
+    Ok(results)
+}
+
+fn visit(dir: &Path, results: &mut BTreeMap<String, f64>) -> anyhow::Result<()> {
+    let new = dir.join("new");
+    let benchmark = new.join("benchmark.json");
+    let estimates = new.join("estimates.json");
+    if benchmark.is_file() && estimates.is_file() {
+        let benchmark: Benchmark = serde_json::from_str(&fs::read_to_string(benchmark)?)?;
+        let estimates: Estimates = serde_json::from_str(&fs::read_to_string(estimates)?)?;
+        results.insert(benchmark.full_id, estimates.median.point_estimate);
+    }
+
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() && !matches!(path.file_name(), Some(n) if n == "new" || n == "base") {
+            visit(&path, results)?;
+        }
+    }
+
+    Ok(())
+}
+
+pub fn current_commit() -> anyhow::Result<String> {
+    let output = Command::new("git").args(["rev-parse", "HEAD"]).output()?;
+    if !output.status.success() {
+        anyhow::bail!("git rev-parse failed")
+    }
+
+    Ok(String::from_utf8(output.stdout)?.trim().to_string())
+}
+
+pub fn load(history: &Path) -> anyhow::Result<Vec<Entry>> {
+    if !history.exists() {
+        return Ok(vec![]);
+    }
+
+    let reader = BufReader::new(fs::File::open(history)?);
+    let mut entries = vec![];
+    for line in reader.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        entries.push(serde_json::from_str(&line)?);
+    }
+
+    Ok(entries)
+}
+
+pub fn append(history: &Path, entry: &Entry) -> anyhow::Result<()> {
+    let mut file = OpenOptions::new().create(true).append(true).open(history)?;
+    writeln!(file, "{}", serde_json::to_string(entry)?)?;
+
+    Ok(())
+}
+
+/// The most recent entry recorded for a different commit, falling back to the
+/// most recent entry when every run so far comes from the current commit.
+pub fn baseline<'a>(entries: &'a [Entry], commit: &str) -> Option<&'a Entry> {
+    entries
+        .iter()
+        .rev()
+        .find(|e| e.commit != commit)
+        .or_else(|| entries.last())
+}
+
+pub fn compare(baseline: &Entry, current: &BTreeMap<String, f64>) -> Vec<Comparison> {
+    current
+        .iter()
+        .filter_map(|(id, current)| {
+            baseline.results.get(id).map(|baseline| Comparison {
+                id: id.clone(),
+                baseline: *baseline,
+                current: *current,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeMap;
+
+    use super::{Entry, baseline, compare};
+
+    fn entry(commit: &str, results: &[(&str, f64)]) -> Entry {
+        Entry {
+            commit: commit.to_string(),
+            timestamp: 0,
+            results: results.iter().map(|(k, v)| (k.to_string(), *v)).collect(),
+        }
+    }
+
+    #[test]
+    fn test_baseline() {
+        let entries = [entry("a", &[]), entry("b", &[]), entry("c", &[])];
+        assert_eq!("b", baseline(&entries, "c").unwrap().commit);
+        assert_eq!("c", baseline(&entries, "d").unwrap().commit);
+        assert!(baseline(&[], "c").is_none());
+    }
+
+    #[test]
+    fn test_compare() {
+        let base = entry("a", &[("aoc2024_01/x", 100.0), ("aoc2024_01/y", 100.0)]);
+        let current = BTreeMap::from([
+            ("aoc2024_01/x".to_string(), 120.0),
+            ("aoc2024_01/y".to_string(), 95.0),
+            ("aoc2024_02/x".to_string(), 10.0),
+        ]);
+
+        let comparisons = compare(&base, &current);
+        assert_eq!(2, comparisons.len());
+        assert!(comparisons[0].is_regression(10.0));
+        assert!(!comparisons[0].is_regression(25.0));
+        assert!(!comparisons[1].is_regression(0.0));
+    }
+
+    #[test]
+    fn test_zero_baseline() {
+        let base = entry("a", &[("aoc2024_01/x", 0.0), ("aoc2024_01/y", 0.0)]);
+        let current = BTreeMap::from([
+            ("aoc2024_01/x".to_string(), 10.0),
+            ("aoc2024_01/y".to_string(), 0.0),
+        ]);
+
+        for c in compare(&base, &current) {
+            assert_eq!(0.0, c.change(), "{}", c.id);
+            assert!(!c.is_regression(0.0));
+        }
+    }
+}
@@ -0,0 +1,129 @@
+use std::{
+    fmt,
+    time::{Duration, Instant},
+};
+
+use crate::{context::Context, solution::Solution};
+
+/// Release-mode time a day may take for each stage on its embedded input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Budget {
+    pub parse: Duration,
+    pub part_one: Duration,
+    pub part_two: Duration,
+}
+
+impl Budget {
+    pub const fn millis(parse: u64, part_one: u64, part_two: u64) -> Self {
+        Self {
+            parse: Duration::from_millis(parse),
+            part_one: Duration::from_millis(part_one),
+            part_two: Duration::from_millis(part_two),
+        }
+    }
+}
+
+/// A stage whose median time went over its budget.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Overrun {
+    pub name: String,
+    pub stage: &'static str,
+    pub median: Duration,
+    pub budget: Duration,
+}
+
+impl fmt::Display for Overrun {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} took {:.2?}, over its budget of {:.2?}",
+            self.name, self.stage, self.median, self.budget
+        )
+    }
+}
+
+fn median(f: impl Fn() -> anyhow::Result<()>, runs: usize) -> anyhow::Result<Duration> {
+    let mut times = Vec::with_capacity(runs);
+    for _ in 0..runs.max(1) {
+        let start = Instant::now();
+        f()?;
+        times.push(start.elapsed());
+    }
+    times.sort();
+
+    Ok(times[times.len() / 2])
+}
+
+/// Times each stage of `solution` `runs` times and returns those whose median
+/// exceeds the budget.
+pub fn check(solution: &Solution, runs: usize) -> anyhow::Result<Vec<Overrun>> {
+    let ctx = Context::default();
+    let solver = (solution.parse)()?;
+    let medians = [
+        (
+            "parse",
+            median(|| (solution.parse)().map(drop), runs)?,
+            solution.budget.parse,
+        ),
+        (
+            "part 1",
+            median(|| solver.part_one(&ctx).map(drop), runs)?,
+            solution.budget.part_one,
+        ),
+        (
+            "part 2",
+            median(|| solver.part_two(&ctx).map(drop), runs)?,
+            solution.budget.part_two,
+        ),
+    ];
+
+    Ok(medians
+        .into_iter()
+        .filter(|(_, median, budget)| median > budget)
+        .map(|(stage, median, budget)| Overrun {
+            name: solution.name(),
+            stage,
+            median,
+            budget,
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use super::{Budget, check};
+    use crate::solution::{Solution, find};
+
+    #[test]
+    fn test_check() {
+        let day1 = find(2024, 1).unwrap();
+        let generous = Solution {
+            budget: Budget::millis(60_000, 60_000, 60_000),
+            ..*day1
+        };
+        assert!(check(&generous, 1).unwrap().is_empty());
+
+        let none = Solution {
+            budget: Budget::millis(0, 0, 0),
+            ..*day1
+        };
+        let overruns = check(&none, 1).unwrap();
+        assert_eq!(3, overruns.len());
+        assert_eq!("part 2", overruns[2].stage);
+        assert_eq!(Duration::ZERO, overruns[2].budget);
+    }
+
+    /// `cargo test --release --features perf-budget budget -- --ignored --test-threads 1`
+    #[cfg(all(feature = "perf-budget", not(debug_assertions)))]
+    #[ignore = "wall-clock budgets need no other tests running"]
+    #[test]
+    fn test_budgets() {
+        let overruns = crate::solution::solutions()
+            .flat_map(|s| check(s, 11).unwrap())
+            .map(|o| o.to_string())
+            .collect::<Vec<_>>();
+        assert!(overruns.is_empty(), "{}", overruns.join("\n"));
+    }
+}
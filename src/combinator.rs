@@ -0,0 +1,122 @@
+//! Parsers for instruction-like inputs, built from small functions.
+//!
+//! A parser matches a prefix of its input and returns the value along with
+//! the rest, or `None` when it doesn't match.
+
+use std::str::FromStr;
+
+/// Anything that matches a prefix of a `&str`.
+pub trait Parser<T>: Fn(&str) -> Option<(T, &str)> {}
+
+impl<T, F: Fn(&str) -> Option<(T, &str)>> Parser<T> for F {}
+
+/// Pins the signature of a closure so its output can borrow its input.
+fn parser<T, F: Fn(&str) -> Option<(T, &str)>>(f: F) -> F {
+    f
+}
+
+/// Exactly `lit`.
+pub fn literal(lit: &'static str) -> impl Parser<()> {
+    parser(move |s| Some(((), s.strip_prefix(lit)?)))
+}
+
+/// Between `min` and `max` ASCII digits, parsed as a `T`.
+pub fn digits<T: FromStr>(min: usize, max: usize) -> impl Parser<T> {
+    parser(move |s| {
+        let len = s.bytes().take(max).take_while(u8::is_ascii_digit).count();
+        if len < min {
+            return None;
+        }
+
+        Some((s[..len].parse().ok()?, &s[len..]))
+    })
+}
+
+pub fn map<A, B>(p: impl Parser<A>, f: impl Fn(A) -> B) -> impl Parser<B> {
+    parser(move |s| p(s).map(|(a, rest)| (f(a), rest)))
+}
+
+/// `a` followed by `b`.
+pub fn pair<A, B>(a: impl Parser<A>, b: impl Parser<B>) -> impl Parser<(A, B)> {
+    parser(move |s| {
+        let (a, rest) = a(s)?;
+        let (b, rest) = b(rest)?;
+
+        Some(((a, b), rest))
+    })
+}
+
+/// `p` after `prefix`, keeping only the value of `p`.
+pub fn preceded<P, A>(prefix: impl Parser<P>, p: impl Parser<A>) -> impl Parser<A> {
+    map(pair(prefix, p), |(_, a)| a)
+}
+
+/// `p` before `suffix`, keeping only the value of `p`.
+pub fn terminated<A, S>(p: impl Parser<A>, suffix: impl Parser<S>) -> impl Parser<A> {
+    map(pair(p, suffix), |(a, _)| a)
+}
+
+/// `a`, or `b` when `a` doesn't match.
+pub fn alt<A>(a: impl Parser<A>, b: impl Parser<A>) -> impl Parser<A> {
+    parser(move |s| a(s).or_else(|| b(s)))
+}
+
+/// Every match of `p` in `text`, trying each position from the left and
+/// resuming after a match.
+pub fn scan<'a, T>(text: &'a str, p: impl Parser<T> + 'a) -> impl Iterator<Item = T> + 'a {
+    let mut rest = text;
+
+    std::iter::from_fn(move || {
+        while !rest.is_empty() {
+            if let Some((value, after)) = p(rest) {
+                rest = after;
+                return Some(value);
+            }
+
+            let mut chars = rest.chars();
+            chars.next();
+            rest = chars.as_str();
+        }
+
+        None
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::{alt, digits, literal, map, pair, preceded, scan, terminated};
+
+    #[test]
+    fn test_digits() {
+        let p = digits::<i32>(1, 3);
+        assert_eq!(Some((12, ",")), p("12,"));
+        assert_eq!(Some((123, "4")), p("1234"));
+        assert_eq!(None, p("x1"));
+        assert_eq!(None, digits::<i32>(2, 3)("1,"));
+    }
+
+    #[test]
+    fn test_sequences() {
+        let point = preceded(
+            literal("<"),
+            terminated(
+                pair(terminated(digits::<u8>(1, 3), literal(",")), digits(1, 3)),
+                literal(">"),
+            ),
+        );
+        assert_eq!(Some(((3, 40), "!")), point("<3,40>!"));
+        assert_eq!(None, point("<3;40>"));
+        assert_eq!(None, point("<300,40"));
+
+        let sign = alt(map(literal("+"), |_| 1), map(literal("-"), |_| -1));
+        assert_eq!(Some((-1, "2")), sign("-2"));
+        assert_eq!(None, sign("2"));
+    }
+
+    #[test]
+    fn test_scan() {
+        let found = scan("a1b22c333é4", digits::<u32>(2, 2)).collect::<Vec<_>>();
+        assert_eq!(vec![22, 33], found);
+        assert_eq!(0, scan("", literal("x")).count());
+    }
+}
@@ -0,0 +1,219 @@
+use std::{
+    fmt::{self, Write},
+    time::{Duration, Instant},
+};
+
+use crate::{context::Context, generate::scaled, solution::Solution};
+
+/// Growth rates a set of timings is fitted against.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Model {
+    Linear,
+    NLogN,
+    Quadratic,
+}
+
+impl Model {
+    const ALL: [Model; 3] = [Model::Linear, Model::NLogN, Model::Quadratic];
+
+    fn apply(self, n: f64) -> f64 {
+        match self {
+            Model::Linear => n,
+            Model::NLogN => n * n.max(2.0).log2(),
+            Model::Quadratic => n * n,
+        }
+    }
+}
+
+impl fmt::Display for Model {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Model::Linear => "O(n)",
+            Model::NLogN => "O(n log n)",
+            Model::Quadratic => "O(n²)",
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Fit {
+    pub model: Model,
+    /// Seconds per unit of the model, `t ≈ coefficient * model(n)`.
+    pub coefficient: f64,
+    /// Mean squared relative error of the fit.
+    pub error: f64,
+}
+
+/// Fits `(n, seconds)` samples to each [`Model`] and returns the closest one.
+///
+/// Errors are relative, so the small sizes weigh as much as the large ones.
+pub fn fit(samples: &[(f64, f64)]) -> Option<Fit> {
+    let samples = samples.iter().filter(|(_, t)| *t > 0.0).collect::<Vec<_>>();
+    if samples.len() < 2 {
+        return None;
+    }
+
+    Model::ALL
+        .into_iter()
+        .map(|model| {
+            // minimizes sum((1 - c * r)²) with r = model(n) / t
+            let ratios = samples
+                .iter()
+                .map(|(n, t)| model.apply(*n) / t)
+                .collect::<Vec<_>>();
+            let coefficient =
+                ratios.iter().sum::<f64>() / ratios.iter().map(|r| r * r).sum::<f64>();
+            let error = ratios
+                .iter()
+                .map(|r| (1.0 - coefficient * r).powi(2))
+                .sum::<f64>()
+                / ratios.len() as f64;
+
+            Fit {
+                model,
+                coefficient,
+                error,
+            }
+        })
+        .min_by(|a, b| a.error.total_cmp(&b.error))
+}
+
+/// Median timings of one stage across input sizes.
+#[derive(Debug, Clone)]
+pub struct Scaling {
+    pub stage: &'static str,
+    /// Input length in bytes and the median time at that length.
+    pub samples: Vec<(usize, Duration)>,
+}
+
+impl Scaling {
+    pub fn fit(&self) -> Option<Fit> {
+        let samples = self
+            .samples
+            .iter()
+            .map(|(n, t)| (*n as f64, t.as_secs_f64()))
+            .collect::<Vec<_>>();
+
+        fit(&samples)
+    }
+}
+
+fn median(mut times: Vec<Duration>) -> Duration {
+    times.sort();
+    times[times.len() / 2]
+}
+
+/// Times parsing and both parts of `solution` on generated inputs of each of
+/// `sizes`, taking the median of `repeats` runs.
+pub fn measure(
+    solution: &Solution,
+    sizes: &[usize],
+    seed: u64,
+    repeats: usize,
+) -> anyhow::Result<Vec<Scaling>> {
+    let ctx = Context::default();
+    let repeats = repeats.max(1);
+    let mut stages = ["parse", "part 1", "part 2"].map(|stage| Scaling {
+        stage,
+        samples: vec![],
+    });
+
+    for &size in sizes {
+        let input = scaled(solution, seed, size);
+
+        let mut times = vec![[Duration::ZERO; 3]; repeats];
+        for t in &mut times {
+            let start = Instant::now();
+            let solver = (solution.parse_input)(&input)?;
+            t[0] = start.elapsed();
+
+            let start = Instant::now();
+            solver.part_one(&ctx)?;
+            t[1] = start.elapsed();
+
+            let start = Instant::now();
+            solver.part_two(&ctx)?;
+            t[2] = start.elapsed();
+        }
+
+        for (i, stage) in stages.iter_mut().enumerate() {
+            let median = median(times.iter().map(|t| t[i]).collect());
+            stage.samples.push((input.len(), median));
+        }
+    }
+
+    Ok(stages.into())
+}
+
+/// Renders the timings of each stage followed by its estimated complexity.
+pub fn table(name: &str, stages: &[Scaling]) -> String {
+    let mut out = String::new();
+    for stage in stages {
+        let _ = write!(out, "{name} {:<6}", stage.stage);
+        for (n, t) in &stage.samples {
+            let _ = write!(out, " {n:>10}b {:>10.2?}", t);
+        }
+
+        match stage.fit() {
+            Some(fit) if fit.model == Model::Quadratic => {
+                let _ = writeln!(out, "  {} QUADRATIC", fit.model);
+            }
+            Some(fit) => {
+                let _ = writeln!(out, "  {}", fit.model);
+            }
+            None => out.push_str("  too fast to fit\n"),
+        }
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Model, fit, measure};
+    use crate::solution::find;
+
+    fn samples(f: impl Fn(f64) -> f64) -> Vec<(f64, f64)> {
+        [1e3, 2e3, 4e3, 8e3, 16e3, 32e3]
+            .iter()
+            .enumerate()
+            .map(|(i, n)| {
+                // a few percent of noise
+                let noise = 1.0 + [0.03, -0.02, 0.01, -0.03, 0.02, -0.01][i];
+                (*n, f(*n) * noise)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_fit() {
+        assert_eq!(Model::Linear, fit(&samples(|n| 3e-9 * n)).unwrap().model);
+        assert_eq!(
+            Model::NLogN,
+            fit(&samples(|n| 3e-9 * n * n.log2())).unwrap().model
+        );
+        assert_eq!(
+            Model::Quadratic,
+            fit(&samples(|n| 1e-12 * n * n)).unwrap().model
+        );
+        assert!(fit(&[(10.0, 1.0)]).is_none());
+    }
+
+    #[test]
+    fn test_measure() {
+        let stages = measure(find(2024, 1).unwrap(), &[10, 20, 40], 0, 1).unwrap();
+        assert_eq!(3, stages.len());
+        assert_eq!("part 1", stages[1].stage);
+
+        let lengths = stages[0].samples.iter().map(|s| s.0).collect::<Vec<_>>();
+        assert!(lengths.windows(2).all(|w| w[0] < w[1]), "{lengths:?}");
+    }
+
+    #[test]
+    fn test_quadratic_stage() {
+        // day 2 retries every level of a bad report on a fresh copy of it
+        let stages = measure(find(2024, 2).unwrap(), &[100, 400, 1600], 0, 5).unwrap();
+        let fit = stages[2].fit().unwrap();
+        assert_eq!(Model::Quadratic, fit.model, "{:?}", stages[2].samples);
+    }
+}
@@ -0,0 +1,151 @@
+use std::{
+    fmt,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+};
+
+pub type ProgressFn = Arc<dyn Fn(&str, usize, usize) + Send + Sync>;
+
+/// Handed to solvers so long-running parts can report progress and stop early.
+#[derive(Clone, Default)]
+pub struct Context {
+    label: String,
+    cancelled: Arc<AtomicBool>,
+    progress: Option<ProgressFn>,
+}
+
+/// Error returned by a solver that stopped because its [`Context`] was cancelled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cancelled;
+
+impl fmt::Display for Cancelled {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "cancelled")
+    }
+}
+
+impl std::error::Error for Cancelled {}
+
+impl Context {
+    pub fn with_progress(mut self, progress: ProgressFn) -> Self {
+        self.progress = Some(progress);
+        self
+    }
+
+    /// A context sharing cancellation and progress reporting, labelled for one stage.
+    pub fn stage(&self, label: impl Into<String>) -> Self {
+        Self {
+            label: label.into(),
+            cancelled: self.cancelled.clone(),
+            progress: self.progress.clone(),
+        }
+    }
+
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    /// Fails with [`Cancelled`] once the context has been cancelled.
+    pub fn check(&self) -> Result<(), Cancelled> {
+        if self.is_cancelled() {
+            return Err(Cancelled);
+        }
+
+        Ok(())
+    }
+
+    /// Runs `f` with a context nobody else can cancel, for callers that want
+    /// the answer without the plumbing.
+    ///
+    /// Panics if `f` fails anyway, rather than passing off a made up answer.
+    pub fn run_uncancelled<T>(f: impl FnOnce(&Context) -> anyhow::Result<T>) -> T {
+        f(&Context::default()).unwrap_or_else(|e| panic!("failed without being cancelled: {e}"))
+    }
+
+    pub fn progress(&self, done: usize, total: usize) {
+        if let Some(progress) = &self.progress {
+            progress(&self.label, done, total);
+        }
+    }
+
+    /// Reports progress and checks for cancellation, for use once per item.
+    pub fn tick(&self, done: usize, total: usize) -> Result<(), Cancelled> {
+        self.progress(done, total);
+        self.check()
+    }
+}
+
+/// Renders `label [#####     ]  50%` with a bar of `width` characters.
+pub fn progress_bar(label: &str, done: usize, total: usize, width: usize) -> String {
+    let ratio = if total == 0 {
+        1.0
+    } else {
+        (done.min(total) as f64) / total as f64
+    };
+    let filled = (ratio * width as f64).round() as usize;
+
+    format!(
+        "{label} [{}{}] {:>3}%",
+        "#".repeat(filled),
+        " ".repeat(width - filled),
+        (ratio * 100.0).round() as usize
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::{Arc, Mutex};
+
+    use super::{Cancelled, Context, progress_bar};
+
+    #[test]
+    fn test_context() {
+        let seen = Arc::new(Mutex::new(vec![]));
+        let sink = seen.clone();
+        let ctx = Context::default().with_progress(Arc::new(move |label, done, total| {
+            sink.lock().unwrap().push((label.to_string(), done, total));
+        }));
+
+        let stage = ctx.stage("part 1");
+        assert_eq!(Ok(()), stage.tick(1, 2));
+
+        ctx.cancel();
+        assert!(stage.is_cancelled());
+        assert_eq!(Err(Cancelled), stage.tick(2, 2));
+        assert_eq!(
+            vec![("part 1".to_string(), 1, 2), ("part 1".to_string(), 2, 2)],
+            *seen.lock().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_run_uncancelled() {
+        assert_eq!(
+            3,
+            Context::run_uncancelled(|ctx| Ok(ctx.check().map(|_| 3)?))
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "failed without being cancelled: bad input")]
+    fn test_run_uncancelled_error() {
+        Context::run_uncancelled(|_| -> anyhow::Result<()> { anyhow::bail!("bad input") });
+    }
+
+    #[test]
+    fn test_progress_bar() {
+        assert_eq!("x [##  ]  50%", progress_bar("x", 1, 2, 4));
+        assert_eq!("x [    ]   0%", progress_bar("x", 0, 2, 4));
+        assert_eq!("x [####] 100%", progress_bar("x", 0, 0, 4));
+    }
+}
@@ -0,0 +1,189 @@
+use std::fmt;
+
+use crate::{
+    context::Context,
+    shrink,
+    solution::{Solution, Solve},
+};
+
+/// A variant whose answer differs from the day's [`Solve`] implementation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Disagreement {
+    pub solution: String,
+    pub variant: &'static str,
+    pub part: u8,
+    pub input: String,
+    pub expected: Result<String, String>,
+    pub actual: Result<String, String>,
+}
+
+impl fmt::Display for Disagreement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let show = |r: &Result<String, String>| match r {
+            Ok(s) => s.clone(),
+            Err(e) => format!("error: {e}"),
+        };
+
+        write!(
+            f,
+            "{} part {} `{}` on {}: expected {}, got {}",
+            self.solution,
+            self.part,
+            self.variant,
+            self.input,
+            show(&self.expected),
+            show(&self.actual)
+        )
+    }
+}
+
+fn answer(solver: &dyn Solve, part: u8, ctx: &Context) -> Result<String, String> {
+    let res = match part {
+        1 => solver.part_one(ctx),
+        _ => solver.part_two(ctx),
+    };
+
+    res.map_err(|e| format!("{e:#}"))
+}
+
+/// Runs every variant of `solution` on `input`, described by `label` in the report.
+pub fn check(solution: &Solution, label: &str, input: &str) -> anyhow::Result<Vec<Disagreement>> {
+    let ctx = Context::default();
+    let solver = (solution.parse_input)(input)?;
+
+    let mut disagreements = vec![];
+    for variant in solution.variants {
+        let expected = answer(solver.as_ref(), variant.part, &ctx);
+        let actual = (variant.solve)(input, &ctx).map_err(|e| format!("{e:#}"));
+
+        if expected != actual {
+            disagreements.push(Disagreement {
+                solution: solution.name(),
+                variant: variant.name,
+                part: variant.part,
+                input: label.to_string(),
+                expected,
+                actual,
+            });
+        }
+    }
+
+    Ok(disagreements)
+}
+
+/// Shrinks `input` to a minimal one on which `disagreement`'s variant still
+/// disagrees, inputs that no longer parse don't count.
+pub fn minimize(solution: &Solution, disagreement: &Disagreement, input: &str) -> String {
+    shrink::shrink(input, |candidate| {
+        check(solution, "", candidate).is_ok_and(|found| {
+            found
+                .iter()
+                .any(|d| d.variant == disagreement.variant && d.part == disagreement.part)
+        })
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use proptest::{collection::vec, prelude::*};
+
+    use super::{check, minimize};
+    use crate::{
+        aoc2024::day02::Report,
+        generate::generate,
+        input::Input,
+        solution::{Solution, Variant, find, solutions},
+        strategy::join_spaced,
+    };
+
+    const REPORTS: &str = "7 6 4 2 1\n1 2 7 8 9\n9 7 6 2 1\n1 3 2 4 5\n8 6 4 4 1\n1 3 6 7 9\n";
+
+    #[test]
+    fn test_real_inputs() {
+        for solution in solutions() {
+            let input = solution.input().unwrap();
+            let disagreements = check(solution, "real input", input.as_str()).unwrap();
+            assert!(disagreements.is_empty(), "{}", disagreements[0]);
+        }
+    }
+
+    #[test]
+    fn test_generated_inputs() {
+        for solution in solutions() {
+            for seed in 0..5 {
+                let input = generate(solution, seed, 200);
+                let disagreements = check(solution, &format!("seed {seed}"), &input).unwrap();
+                assert!(disagreements.is_empty(), "{}", disagreements[0]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_examples() {
+        let disagreements = check(find(2024, 2).unwrap(), "example", REPORTS).unwrap();
+        assert!(disagreements.is_empty(), "{}", disagreements[0]);
+    }
+
+    #[test]
+    fn test_report_disagreement() {
+        const ALWAYS_ZERO: &[Variant] = &[Variant {
+            name: "always_zero",
+            part: 2,
+            solve: |_, _| Ok("0".to_string()),
+        }];
+        let day2 = find(2024, 2).unwrap();
+        let broken = Solution {
+            variants: ALWAYS_ZERO,
+            ..*day2
+        };
+
+        let disagreements = check(&broken, "example", REPORTS).unwrap();
+        assert_eq!(1, disagreements.len());
+        assert_eq!(
+            "2024/02 part 2 `always_zero` on example: expected 4, got 0",
+            disagreements[0].to_string()
+        );
+    }
+
+    #[test]
+    fn test_minimize() {
+        // wrong as soon as any report has a level of 5
+        const OFF_BY_FIVE: &[Variant] = &[Variant {
+            name: "off_by_five",
+            part: 2,
+            solve: |input, _| {
+                let fives = input.split_whitespace().any(|t| t == "5") as i32;
+                let report = Report::new_from_input(&Input::new(input))?;
+                Ok((report.sum_of_dampened_reports() - fives).to_string())
+            },
+        }];
+        let day2 = find(2024, 2).unwrap();
+        let broken = Solution {
+            variants: OFF_BY_FIVE,
+            ..*day2
+        };
+
+        let input = day2.input().unwrap();
+        let disagreements = check(&broken, "real input", input.as_str()).unwrap();
+        assert_eq!(1, disagreements.len());
+        assert_eq!("5", minimize(&broken, &disagreements[0], input.as_str()));
+    }
+
+    fn reports() -> impl Strategy<Value = String> {
+        // small levels so that safe and almost safe reports are common
+        vec(vec(0..12i32, 1..9), 1..20).prop_flat_map(|data| {
+            data.iter()
+                .map(|r| join_spaced(r.iter().map(|v| v.to_string()).collect()))
+                .collect::<Vec<_>>()
+                .prop_map(|l| l.join("\n"))
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn test_random_reports(input in reports()) {
+            let disagreements = check(find(2024, 2).unwrap(), &input, &input).unwrap();
+            prop_assert!(disagreements.is_empty(), "{}", disagreements[0]);
+        }
+    }
+}
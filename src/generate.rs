@@ -0,0 +1,111 @@
+use crate::solution::Solution;
+
+/// Small seeded generator (SplitMix64), so that a seed gives the same input
+/// on every platform and version.
+#[derive(Debug, Clone)]
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+
+        z ^ (z >> 31)
+    }
+
+    /// Uniform in `0..n`, `n` must not be zero.
+    pub fn below(&mut self, n: usize) -> usize {
+        (self.next_u64() % n as u64) as usize
+    }
+
+    /// Uniform in `low..high`.
+    pub fn range(&mut self, low: i32, high: i32) -> i32 {
+        low + self.below((high - low) as usize) as i32
+    }
+
+    pub fn chance(&mut self, p: f64) -> bool {
+        ((self.next_u64() >> 11) as f64 / (1u64 << 53) as f64) < p
+    }
+
+    pub fn choose<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len())]
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.below(i + 1));
+        }
+    }
+}
+
+/// A valid input for `solution` whose size grows with `size`, see each day's
+/// `generate` for what it counts.
+pub fn generate(solution: &Solution, seed: u64, size: usize) -> String {
+    (solution.generate)(&mut Rng::new(seed), size)
+}
+
+/// The input `aoc scale` times for `size`, from the day's scale generator if
+/// it has one.
+pub fn scaled(solution: &Solution, seed: u64, size: usize) -> String {
+    (solution.scale.unwrap_or(solution.generate))(&mut Rng::new(seed), size)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Rng, generate};
+    use crate::{
+        context::Context,
+        solution::{find, solutions},
+    };
+
+    #[test]
+    fn test_rng() {
+        let mut rng = Rng::new(7);
+        let first = (0..5).map(|_| rng.next_u64()).collect::<Vec<_>>();
+        let mut rng = Rng::new(7);
+        assert_eq!(first, (0..5).map(|_| rng.next_u64()).collect::<Vec<_>>());
+
+        assert!((0..1000).all(|_| (3..9).contains(&rng.range(3, 9))));
+        let mut items = (0..20).collect::<Vec<_>>();
+        rng.shuffle(&mut items);
+        items.sort();
+        assert_eq!((0..20).collect::<Vec<_>>(), items);
+    }
+
+    #[test]
+    fn test_generate() {
+        for solution in solutions() {
+            let input = generate(solution, 1, 50);
+            assert_eq!(input, generate(solution, 1, 50), "{}", solution.name());
+            assert_ne!(input, generate(solution, 2, 50), "{}", solution.name());
+
+            let solver = (solution.parse_input)(&input).unwrap();
+            let ctx = Context::default();
+            solver.part_one(&ctx).unwrap();
+            solver.part_two(&ctx).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_largest_sizes() {
+        // a million lines of locations and a megabyte of memory, with totals
+        // that don't fit in an i32
+        for day in [1, 3] {
+            let solution = find(2024, day).unwrap();
+            let solver = (solution.parse_input)(&generate(solution, 1, 1_000_000)).unwrap();
+            let ctx = Context::default();
+            let answers = [solver.part_one(&ctx), solver.part_two(&ctx)]
+                .map(|a| a.unwrap().parse::<i64>().unwrap());
+            assert!(
+                answers.iter().any(|a| *a > i64::from(i32::MAX)),
+                "day {day}: {answers:?}"
+            );
+        }
+    }
+}
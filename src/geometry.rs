@@ -0,0 +1,267 @@
+use std::{
+    ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign},
+    str::FromStr,
+};
+
+/// A point on a grid, `x` grows to the right and `y` downwards, so `(0, 0)`
+/// is the top left. The operators use plain `i32` arithmetic, so they panic on
+/// overflow in debug builds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Pos {
+    pub x: i32,
+    pub y: i32,
+}
+
+impl Pos {
+    pub const fn new(x: i32, y: i32) -> Self {
+        Self { x, y }
+    }
+
+    /// The neighbor one step towards `direction`.
+    pub fn step(self, direction: Direction) -> Self {
+        self + direction.unit()
+    }
+
+    /// Like [`Self::step`], but `None` instead of overflowing.
+    pub fn checked_step(self, direction: Direction) -> Option<Self> {
+        let unit = direction.unit();
+
+        Some(Pos::new(
+            self.x.checked_add(unit.x)?,
+            self.y.checked_add(unit.y)?,
+        ))
+    }
+
+    /// The positions after this one going towards `direction`, ending only
+    /// where the coordinates would overflow.
+    pub fn ray(self, direction: Direction) -> impl Iterator<Item = Pos> {
+        std::iter::successors(self.checked_step(direction), move |p| {
+            p.checked_step(direction)
+        })
+    }
+
+    /// Steps needed to reach `other` moving only in cardinal directions.
+    pub fn manhattan(self, other: Pos) -> i32 {
+        (self.x - other.x).abs() + (self.y - other.y).abs()
+    }
+
+    /// Steps needed to reach `other` moving diagonals too.
+    pub fn chebyshev(self, other: Pos) -> i32 {
+        (self.x - other.x).abs().max((self.y - other.y).abs())
+    }
+}
+
+impl Add for Pos {
+    type Output = Pos;
+
+    fn add(self, rhs: Pos) -> Pos {
+        Pos::new(self.x + rhs.x, self.y + rhs.y)
+    }
+}
+
+impl Sub for Pos {
+    type Output = Pos;
+
+    fn sub(self, rhs: Pos) -> Pos {
+        Pos::new(self.x - rhs.x, self.y - rhs.y)
+    }
+}
+
+impl AddAssign for Pos {
+    fn add_assign(&mut self, rhs: Pos) {
+        *self = *self + rhs;
+    }
+}
+
+impl SubAssign for Pos {
+    fn sub_assign(&mut self, rhs: Pos) {
+        *self = *self - rhs;
+    }
+}
+
+impl Mul<i32> for Pos {
+    type Output = Pos;
+
+    fn mul(self, rhs: i32) -> Pos {
+        Pos::new(self.x * rhs, self.y * rhs)
+    }
+}
+
+impl Neg for Pos {
+    type Output = Pos;
+
+    fn neg(self) -> Pos {
+        Pos::new(-self.x, -self.y)
+    }
+}
+
+/// One of the eight ways to leave a cell, with up being towards `y = 0`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    Up,
+    UpRight,
+    Right,
+    DownRight,
+    Down,
+    DownLeft,
+    Left,
+    UpLeft,
+}
+
+impl Direction {
+    /// Every direction, clockwise from up.
+    pub const ALL: [Direction; 8] = [
+        Direction::Up,
+        Direction::UpRight,
+        Direction::Right,
+        Direction::DownRight,
+        Direction::Down,
+        Direction::DownLeft,
+        Direction::Left,
+        Direction::UpLeft,
+    ];
+
+    pub const CARDINAL: [Direction; 4] = [
+        Direction::Up,
+        Direction::Right,
+        Direction::Down,
+        Direction::Left,
+    ];
+
+    pub const DIAGONAL: [Direction; 4] = [
+        Direction::UpRight,
+        Direction::DownRight,
+        Direction::DownLeft,
+        Direction::UpLeft,
+    ];
+
+    /// The direction `eighths` of a turn clockwise, variants are in that order.
+    fn rotate(self, eighths: usize) -> Self {
+        Self::ALL[(self as usize + eighths) % 8]
+    }
+
+    /// A quarter turn clockwise.
+    pub fn turn_right(self) -> Self {
+        self.rotate(2)
+    }
+
+    /// A quarter turn counterclockwise.
+    pub fn turn_left(self) -> Self {
+        self.rotate(6)
+    }
+
+    pub fn turn_around(self) -> Self {
+        self.rotate(4)
+    }
+
+    pub fn is_diagonal(self) -> bool {
+        Self::DIAGONAL.contains(&self)
+    }
+
+    /// The offset of one step this way.
+    pub fn unit(self) -> Pos {
+        match self {
+            Direction::Up => Pos::new(0, -1),
+            Direction::UpRight => Pos::new(1, -1),
+            Direction::Right => Pos::new(1, 0),
+            Direction::DownRight => Pos::new(1, 1),
+            Direction::Down => Pos::new(0, 1),
+            Direction::DownLeft => Pos::new(-1, 1),
+            Direction::Left => Pos::new(-1, 0),
+            Direction::UpLeft => Pos::new(-1, -1),
+        }
+    }
+
+    /// Reads a cardinal direction written as `^v<>`, `NSEW` or `UDLR`.
+    pub fn from_char(c: char) -> Option<Self> {
+        match c {
+            '^' | 'N' | 'U' => Some(Direction::Up),
+            'v' | 'S' | 'D' => Some(Direction::Down),
+            '<' | 'W' | 'L' => Some(Direction::Left),
+            '>' | 'E' | 'R' => Some(Direction::Right),
+            _ => None,
+        }
+    }
+}
+
+impl FromStr for Direction {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars();
+        match (chars.next().and_then(Direction::from_char), chars.next()) {
+            (Some(direction), None) => Ok(direction),
+            _ => Err(format!("`{s}` is not a direction")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Direction, Pos};
+
+    #[test]
+    fn test_turns() {
+        assert_eq!(Direction::Right, Direction::Up.turn_right());
+        assert_eq!(Direction::Left, Direction::Up.turn_left());
+        assert_eq!(Direction::DownLeft, Direction::UpRight.turn_around());
+        assert_eq!(Direction::DownRight, Direction::UpRight.turn_right());
+
+        for d in Direction::ALL {
+            assert_eq!(d, d.turn_right().turn_left());
+            assert_eq!(-d.unit(), d.turn_around().unit());
+            assert_eq!(d.is_diagonal(), !Direction::CARDINAL.contains(&d));
+        }
+    }
+
+    #[test]
+    fn test_parse() {
+        for s in ["^v<>", "NSWE", "UDLR"] {
+            let parsed = s.chars().map(Direction::from_char).collect::<Vec<_>>();
+            assert_eq!(
+                vec![
+                    Some(Direction::Up),
+                    Some(Direction::Down),
+                    Some(Direction::Left),
+                    Some(Direction::Right)
+                ],
+                parsed,
+                "{s}"
+            );
+        }
+
+        assert_eq!(Ok(Direction::Left), "L".parse());
+        assert!("x".parse::<Direction>().is_err());
+        assert!("NE".parse::<Direction>().is_err());
+        assert!("".parse::<Direction>().is_err());
+    }
+
+    #[test]
+    fn test_pos() {
+        let (a, b) = (Pos::new(1, 2), Pos::new(-3, 5));
+        assert_eq!(Pos::new(-2, 7), a + b);
+        assert_eq!(Pos::new(4, -3), a - b);
+        assert_eq!(Pos::new(3, 6), a * 3);
+        assert_eq!(7, a.manhattan(b));
+        assert_eq!(4, a.chebyshev(b));
+
+        let mut c = a;
+        c += b;
+        c -= a;
+        assert_eq!(b, c);
+
+        assert_eq!(Pos::new(2, 2), a.step(Direction::Right));
+        assert_eq!(
+            vec![Pos::new(0, 3), Pos::new(-1, 4)],
+            a.ray(Direction::DownLeft).take(2).collect::<Vec<_>>()
+        );
+
+        let edge = Pos::new(i32::MAX - 2, 0);
+        assert_eq!(2, edge.ray(Direction::Right).count());
+        assert_eq!(None, Pos::new(0, i32::MIN).checked_step(Direction::UpLeft));
+        assert_eq!(
+            Some(Pos::new(1, 1)),
+            Pos::new(0, 0).checked_step(Direction::DownRight)
+        );
+    }
+}
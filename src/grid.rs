@@ -0,0 +1,317 @@
+use std::{
+    fmt,
+    ops::{Index, IndexMut},
+};
+
+use crate::geometry::{Direction, Pos};
+
+/// A rectangle of cells stored row after row.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Self {
+        Self {
+            width,
+            height,
+            cells: vec![fill; width * height],
+        }
+    }
+
+    /// Swaps rows and columns.
+    pub fn transpose(&self) -> Self {
+        self.remap(self.height, self.width, |p| Pos::new(p.y, p.x))
+    }
+
+    /// Turns the grid a quarter clockwise.
+    pub fn rotate_right(&self) -> Self {
+        let h = self.height as i32;
+        self.remap(self.height, self.width, |p| Pos::new(p.y, h - 1 - p.x))
+    }
+
+    /// Turns the grid a quarter counterclockwise.
+    pub fn rotate_left(&self) -> Self {
+        let w = self.width as i32;
+        self.remap(self.height, self.width, |p| Pos::new(w - 1 - p.y, p.x))
+    }
+
+    /// Mirrors the grid left to right.
+    pub fn flip_horizontal(&self) -> Self {
+        let w = self.width as i32;
+        self.remap(self.width, self.height, |p| Pos::new(w - 1 - p.x, p.y))
+    }
+
+    /// Mirrors the grid top to bottom.
+    pub fn flip_vertical(&self) -> Self {
+        let h = self.height as i32;
+        self.remap(self.width, self.height, |p| Pos::new(p.x, h - 1 - p.y))
+    }
+
+    /// A `width` x `height` grid whose cell at `p` is ours at `source(p)`.
+    fn remap(&self, width: usize, height: usize, source: impl Fn(Pos) -> Pos) -> Self {
+        let mut cells = Vec::with_capacity(self.cells.len());
+        for y in 0..height {
+            for x in 0..width {
+                cells.push(self[source(Pos::new(x as i32, y as i32))].clone());
+            }
+        }
+
+        Self {
+            width,
+            height,
+            cells,
+        }
+    }
+}
+
+impl<T> Grid<T> {
+    /// A grid over `cells` in row order, `width` must divide their number.
+    pub fn from_vec(width: usize, cells: Vec<T>) -> Self {
+        assert!(
+            cells.len().is_multiple_of(width),
+            "{} cells don't make rows of {width}",
+            cells.len()
+        );
+
+        Self {
+            width,
+            height: cells.len().checked_div(width).unwrap_or(0),
+            cells,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn contains(&self, pos: Pos) -> bool {
+        (0..self.width as i32).contains(&pos.x) && (0..self.height as i32).contains(&pos.y)
+    }
+
+    fn offset(&self, pos: Pos) -> Option<usize> {
+        self.contains(pos)
+            .then(|| pos.y as usize * self.width + pos.x as usize)
+    }
+
+    pub fn get(&self, pos: Pos) -> Option<&T> {
+        self.offset(pos).map(|i| &self.cells[i])
+    }
+
+    pub fn get_mut(&mut self, pos: Pos) -> Option<&mut T> {
+        self.offset(pos).map(|i| &mut self.cells[i])
+    }
+
+    /// Replaces the cell at `pos`, returning the old value or `None` when out
+    /// of bounds.
+    pub fn set(&mut self, pos: Pos, value: T) -> Option<T> {
+        self.get_mut(pos).map(|cell| std::mem::replace(cell, value))
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        (0..self.height).map(|y| self.row(y))
+    }
+
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        assert!(x < self.width, "column {x} of {}", self.width);
+        self.cells.iter().skip(x).step_by(self.width)
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(|x| self.column(x))
+    }
+
+    /// Cells from `start` towards `direction` until leaving the grid.
+    fn line(&self, start: Pos, direction: Direction) -> impl Iterator<Item = &T> {
+        std::iter::once(start)
+            .chain(start.ray(direction))
+            .map_while(|p| self.get(p))
+    }
+
+    /// Each diagonal going down to the right, starting from the bottom left
+    /// corner.
+    pub fn diagonals(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        let (w, h) = (self.width as i32, self.height as i32);
+        let starts = (0..h).rev().map(|y| Pos::new(0, y));
+        let starts = starts.chain((1..w).map(|x| Pos::new(x, 0)));
+
+        starts.map(|start| self.line(start, Direction::DownRight))
+    }
+
+    /// Each diagonal going down to the left, starting from the top left
+    /// corner.
+    pub fn anti_diagonals(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        let (w, h) = (self.width as i32, self.height as i32);
+        let starts = (0..w).map(|x| Pos::new(x, 0));
+        let starts = starts.chain((1..h).map(move |y| Pos::new(w - 1, y)));
+
+        starts.map(|start| self.line(start, Direction::DownLeft))
+    }
+
+    /// Every position, row after row.
+    pub fn positions(&self) -> impl Iterator<Item = Pos> + use<T> {
+        let width = self.width;
+        (0..self.cells.len()).map(move |i| Pos::new((i % width) as i32, (i / width) as i32))
+    }
+
+    /// Every cell with its position, row after row.
+    pub fn iter(&self) -> impl Iterator<Item = (Pos, &T)> {
+        self.positions().zip(&self.cells)
+    }
+
+    /// The positions up, right, down and left of `pos` inside the grid.
+    pub fn neighbors4(&self, pos: Pos) -> impl Iterator<Item = Pos> {
+        self.neighbors(pos, &Direction::CARDINAL)
+    }
+
+    /// [`Self::neighbors4`] and the diagonals.
+    pub fn neighbors8(&self, pos: Pos) -> impl Iterator<Item = Pos> {
+        self.neighbors(pos, &Direction::ALL)
+    }
+
+    fn neighbors(&self, pos: Pos, directions: &'static [Direction]) -> impl Iterator<Item = Pos> {
+        directions
+            .iter()
+            .map(move |d| pos.step(*d))
+            .filter(|p| self.contains(*p))
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+}
+
+impl<T: PartialEq> Grid<T> {
+    /// The first position holding `value`, row after row.
+    pub fn find(&self, value: &T) -> Option<Pos> {
+        self.find_all(value).next()
+    }
+
+    pub fn find_all<'a>(&'a self, value: &'a T) -> impl Iterator<Item = Pos> + 'a {
+        self.iter()
+            .filter(move |(_, v)| *v == value)
+            .map(|(p, _)| p)
+    }
+}
+
+impl<T> Index<Pos> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: Pos) -> &T {
+        match self.get(pos) {
+            Some(cell) => cell,
+            None => panic!("{pos:?} is outside a {}x{} grid", self.width, self.height),
+        }
+    }
+}
+
+impl<T> IndexMut<Pos> for Grid<T> {
+    fn index_mut(&mut self, pos: Pos) -> &mut T {
+        let (width, height) = (self.width, self.height);
+        match self.get_mut(pos) {
+            Some(cell) => cell,
+            None => panic!("{pos:?} is outside a {width}x{height} grid"),
+        }
+    }
+}
+
+/// One line per row, with the cells side by side.
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in self.rows() {
+            for cell in row {
+                write!(f, "{cell}")?;
+            }
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Grid;
+    use crate::geometry::Pos;
+
+    fn grid(text: &str) -> Grid<char> {
+        let width = text.lines().next().map_or(0, |l| l.len());
+        Grid::from_vec(width, text.lines().flat_map(str::chars).collect())
+    }
+
+    fn strings<'a>(lines: impl Iterator<Item = impl Iterator<Item = &'a char>>) -> Vec<String> {
+        lines.map(|l| l.collect()).collect()
+    }
+
+    #[test]
+    fn test_access() {
+        let mut g = grid("abc\ndef");
+        assert_eq!((3, 2), (g.width(), g.height()));
+        assert_eq!(Some(&'f'), g.get(Pos::new(2, 1)));
+        assert_eq!(None, g.get(Pos::new(1, 2)));
+        assert_eq!(None, g.get(Pos::new(-1, 0)));
+        assert_eq!(Some('a'), g.set(Pos::new(0, 0), 'z'));
+        assert_eq!(None, g.set(Pos::new(3, 0), 'z'));
+        assert_eq!('z', g[Pos::new(0, 0)]);
+
+        assert_eq!(vec!["zbc", "def"], strings(g.rows().map(|r| r.iter())));
+        assert_eq!(vec!["zd", "be", "cf"], strings(g.columns()));
+        assert_eq!(Some(Pos::new(1, 1)), g.find(&'e'));
+        assert_eq!(None, g.find(&'x'));
+        assert_eq!("zbc\ndef\n", g.to_string());
+    }
+
+    #[test]
+    fn test_diagonals() {
+        let g = grid("abc\ndef");
+        assert_eq!(vec!["d", "ae", "bf", "c"], strings(g.diagonals()));
+        assert_eq!(vec!["a", "bd", "ce", "f"], strings(g.anti_diagonals()));
+        assert_eq!(0, Grid::<char>::default().diagonals().count());
+    }
+
+    #[test]
+    fn test_neighbors() {
+        let g = grid("abc\ndef\nghi");
+        assert_eq!(4, g.neighbors4(Pos::new(1, 1)).count());
+        assert_eq!(8, g.neighbors8(Pos::new(1, 1)).count());
+        assert_eq!(
+            vec![Pos::new(1, 0), Pos::new(0, 1)],
+            g.neighbors4(Pos::new(0, 0)).collect::<Vec<_>>()
+        );
+        assert_eq!(3, g.neighbors8(Pos::new(2, 2)).count());
+    }
+
+    #[test]
+    fn test_transforms() {
+        let g = grid("abc\ndef");
+        assert_eq!("ad\nbe\ncf\n", g.transpose().to_string());
+        assert_eq!("da\neb\nfc\n", g.rotate_right().to_string());
+        assert_eq!("cf\nbe\nad\n", g.rotate_left().to_string());
+        assert_eq!("cba\nfed\n", g.flip_horizontal().to_string());
+        assert_eq!("def\nabc\n", g.flip_vertical().to_string());
+        assert_eq!(
+            g,
+            g.rotate_right().rotate_right().rotate_left().rotate_left()
+        );
+        assert_eq!(g, g.transpose().transpose());
+    }
+}
@@ -0,0 +1,567 @@
+use std::{any::type_name, borrow::Cow, fmt, str::FromStr};
+
+pub use aoc_derive::FromLine;
+
+use crate::{Asset, grid::Grid};
+
+/// Why a puzzle input was rejected. Lines are numbered from 1.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InputError {
+    /// A line that doesn't have the shape the day expects.
+    Malformed { line: usize, reason: String },
+    /// A grid row whose length differs from the first row.
+    Ragged {
+        line: usize,
+        expected: usize,
+        found: usize,
+    },
+    /// An update with an even number of pages has no middle page.
+    EvenUpdate { line: usize, pages: usize },
+    /// Rules that order these pages of an update in a cycle, so it can't be
+    /// sorted.
+    CyclicRules { pages: Vec<i32> },
+}
+
+impl InputError {
+    pub fn malformed(line: usize, reason: impl Into<String>) -> Self {
+        InputError::Malformed {
+            line,
+            reason: reason.into(),
+        }
+    }
+}
+
+impl fmt::Display for InputError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InputError::Malformed { line, reason } => write!(f, "line {line}: {reason}"),
+            InputError::Ragged {
+                line,
+                expected,
+                found,
+            } => write!(
+                f,
+                "line {line}: expected {expected} columns like the first row, found {found}"
+            ),
+            InputError::EvenUpdate { line, pages } => {
+                write!(f, "line {line}: update of {pages} pages has no middle page")
+            }
+            InputError::CyclicRules { pages } => {
+                write!(f, "rules order pages {pages:?} in a cycle")
+            }
+        }
+    }
+}
+
+impl std::error::Error for InputError {}
+
+fn is_normal(input: &str) -> bool {
+    !input.starts_with('\u{feff}')
+        && !input.contains(['\r', '\t'])
+        && input.lines().all(|l| !l.ends_with(' '))
+        && (input.is_empty() || input.ends_with('\n'))
+        && !input.ends_with("\n\n")
+        && input != "\n"
+}
+
+/// Puts `input` in the form every parser expects: no byte order mark, `\n`
+/// line endings, tabs as spaces, no trailing whitespace on a line and no
+/// trailing blank lines. Borrows when there is nothing to change.
+pub fn normalize(input: &str) -> Cow<'_, str> {
+    if is_normal(input) {
+        return Cow::Borrowed(input);
+    }
+
+    let input = input.strip_prefix('\u{feff}').unwrap_or(input);
+    let mut lines = input.lines().map(str::trim_end).collect::<Vec<_>>();
+    while lines.last() == Some(&"") {
+        lines.pop();
+    }
+
+    let mut out = String::with_capacity(input.len());
+    for line in lines {
+        out.push_str(&line.replace('\t', " "));
+        out.push('\n');
+    }
+
+    Cow::Owned(out)
+}
+
+/// The integers of `line` between each `sep`, with spaces around them
+/// allowed. A `sep` of `' '` takes any run of whitespace. The first field that
+/// isn't an integer comes back as its text, empty when it is missing.
+pub fn ints<T: FromStr>(line: &str, sep: char) -> Result<Vec<T>, &str> {
+    fn parse<T: FromStr>(field: &str) -> Result<T, &str> {
+        let field = field.trim();
+        field.parse().map_err(|_| field)
+    }
+
+    match sep {
+        ' ' => line.split_whitespace().map(parse).collect(),
+        sep => line.split(sep).map(parse).collect(),
+    }
+}
+
+/// A value parsed from one line of input, the error being the reason it
+/// doesn't fit. Usually derived from a pattern like
+/// `#[line("{before}|{after}")]` naming the struct's fields.
+pub trait FromLine: Sized {
+    fn from_line(line: &str) -> Result<Self, String>;
+}
+
+/// Splits `line` on the `literals` around the fields of a [`FromLine`]
+/// pattern. Separators of only whitespace match any run of whitespace, and a
+/// sign starting a field stays with it even when the separator is `-` or `+`.
+#[doc(hidden)]
+pub fn fields<'a, const N: usize>(
+    line: &'a str,
+    literals: &[&str],
+    pattern: &str,
+) -> Result<[&'a str; N], String> {
+    let mismatch = || format!("expected a line like `{pattern}`");
+    let mut rest = line
+        .trim()
+        .strip_prefix(literals[0].trim())
+        .ok_or_else(mismatch)?;
+
+    let mut fields = [""; N];
+    for (i, field) in fields.iter_mut().enumerate() {
+        let sep = literals[i + 1].trim();
+        let (value, after) = if i + 1 == N {
+            (rest.strip_suffix(sep).ok_or_else(mismatch)?, "")
+        } else if sep.is_empty() {
+            rest.trim_start()
+                .split_once(char::is_whitespace)
+                .ok_or_else(mismatch)?
+        } else {
+            // a leading sign is part of the value, so `-1-2` splits on the second `-`
+            let value = rest.trim_start();
+            let skip = rest.len() - value.len() + usize::from(value.starts_with(['-', '+']));
+            let at = skip + rest[skip..].find(sep).ok_or_else(mismatch)?;
+
+            (&rest[..at], &rest[at + sep.len()..])
+        };
+
+        *field = value.trim();
+        rest = after;
+    }
+    if N == 0 && !rest.trim().is_empty() {
+        return Err(mismatch());
+    }
+
+    Ok(fields)
+}
+
+/// Parses the text of the field `name` of a [`FromLine`] pattern.
+#[doc(hidden)]
+pub fn field<T: FromStr>(text: &str, name: &str) -> Result<T, String> {
+    if text.is_empty() {
+        return Err(format!("missing `{name}`"));
+    }
+
+    text.parse().map_err(|_| {
+        format!(
+            "`{text}` is not a valid {} for `{name}`",
+            short_type_name::<T>()
+        )
+    })
+}
+
+/// `T` without module paths, `Vec<String>` rather than
+/// `alloc::vec::Vec<alloc::string::String>`.
+fn short_type_name<T>() -> String {
+    type_name::<T>()
+        .split_inclusive(|c: char| !(c.is_alphanumeric() || c == '_' || c == ':'))
+        .map(|part| part.rsplit("::").next().unwrap_or(part))
+        .collect()
+}
+
+/// Puzzle text in normal form, borrowed from the embedded data or the caller
+/// unless [`normalize`] had to change it.
+#[derive(Debug, Clone)]
+pub struct Input<'a> {
+    text: Cow<'a, str>,
+}
+
+impl Input<'static> {
+    /// The embedded input at `path` below `resources`, e.g. `2024/1.txt`.
+    #[tracing::instrument(fields(size))]
+    pub fn embedded(path: &str) -> anyhow::Result<Self> {
+        let Some(asset) = Asset::get(path) else {
+            anyhow::bail!("no embedded input {path}")
+        };
+        tracing::Span::current().record("size", asset.data.len());
+
+        let text = match asset.data {
+            Cow::Borrowed(bytes) => normalize(std::str::from_utf8(bytes)?),
+            Cow::Owned(bytes) => Cow::Owned(normalize(&String::from_utf8(bytes)?).into_owned()),
+        };
+
+        Ok(Self { text })
+    }
+}
+
+impl<'a> Input<'a> {
+    pub fn new(text: &'a str) -> Self {
+        Self {
+            text: normalize(text),
+        }
+    }
+
+    /// Whether the text is still the caller's or the embedded data, not a copy.
+    pub fn is_borrowed(&self) -> bool {
+        matches!(self.text, Cow::Borrowed(_))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.text
+    }
+
+    /// The text itself, still borrowed if it was.
+    pub fn into_text(self) -> Cow<'a, str> {
+        self.text
+    }
+
+    pub fn lines(&self) -> std::str::Lines<'_> {
+        self.text.lines()
+    }
+
+    /// Groups of lines separated by blank lines, each with the index of its
+    /// first line.
+    pub fn sections(&self) -> impl Iterator<Item = (usize, &str)> {
+        let mut rest = self.as_str();
+        let mut line = 0;
+
+        std::iter::from_fn(move || {
+            while let Some(r) = rest.strip_prefix('\n') {
+                rest = r;
+                line += 1;
+            }
+            if rest.is_empty() {
+                return None;
+            }
+
+            let end = rest.find("\n\n").map_or(rest.len(), |i| i + 1);
+            let (section, first) = (&rest[..end], line);
+            line += section.matches('\n').count();
+            rest = &rest[end..];
+
+            Some((first, section.trim_end_matches('\n')))
+        })
+    }
+
+    /// The non-blank lines as rows of characters, all as wide as the first.
+    pub fn grid(&self) -> Result<Grid<char>, InputError> {
+        let mut cells = vec![];
+        let mut width = None;
+        for (i, line) in self.lines().enumerate() {
+            if line.is_empty() {
+                continue;
+            }
+
+            let before = cells.len();
+            cells.extend(line.chars());
+            let found = cells.len() - before;
+            match width {
+                None => width = Some(found),
+                Some(expected) if expected != found => {
+                    return Err(InputError::Ragged {
+                        line: i + 1,
+                        expected,
+                        found,
+                    });
+                }
+                Some(_) => {}
+            }
+        }
+
+        Ok(Grid::from_vec(width.unwrap_or(0), cells))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::borrow::Cow;
+
+    use super::{FromLine, Input, InputError, field, ints, normalize, short_type_name};
+    use crate::{context::Context, generate::generate, solution::find};
+
+    type Expected = Result<(&'static str, &'static str), InputError>;
+
+    fn answers(day: u8, input: &str) -> Result<(String, String), InputError> {
+        let solver = (find(2024, day).unwrap().parse_input)(input)
+            .map_err(|e| e.downcast::<InputError>().unwrap())?;
+        let ctx = Context::default();
+
+        let solve = |answer: anyhow::Result<String>| {
+            answer.map_err(|e| e.downcast::<InputError>().unwrap())
+        };
+
+        Ok((solve(solver.part_one(&ctx))?, solve(solver.part_two(&ctx))?))
+    }
+
+    #[test]
+    fn test_degenerate_inputs() {
+        let cases: &[(u8, &str, Expected)] = &[
+            (1, "3 4", Ok(("1", "0"))),
+            (1, "3 4\n\n4 3\n", Ok(("0", "7"))),
+            (
+                1,
+                "3",
+                Err(InputError::malformed(
+                    1,
+                    "expected a line like `{left} {right}`",
+                )),
+            ),
+            (
+                1,
+                "1 2\n3 4 5",
+                Err(InputError::malformed(
+                    2,
+                    "`4 5` is not a valid i32 for `right`",
+                )),
+            ),
+            (
+                1,
+                "3 x",
+                Err(InputError::malformed(
+                    1,
+                    "`x` is not a valid i32 for `right`",
+                )),
+            ),
+            (2, "7", Ok(("1", "1"))),
+            (2, "1 2\n\n9 1", Ok(("1", "2"))),
+            (
+                2,
+                "1 2 x",
+                Err(InputError::malformed(1, "`x` is not a level")),
+            ),
+            (
+                2,
+                "1,2",
+                Err(InputError::malformed(1, "`1,2` is not a level")),
+            ),
+            (3, "mul(2,3)", Ok(("6", "6"))),
+            (3, "don't()", Ok(("0", "0"))),
+            (4, "X", Ok(("0", "0"))),
+            (4, "XMAS", Ok(("1", "0"))),
+            (4, "M.S\n.A.\nM.S\n", Ok(("0", "1"))),
+            (
+                4,
+                "XMAS\nXM",
+                Err(InputError::Ragged {
+                    line: 2,
+                    expected: 4,
+                    found: 2,
+                }),
+            ),
+            (5, "1|2\n1|3\n2|3\n\n1,2,3", Ok(("2", "0"))),
+            (5, "1|2\n2|3\n1|3\n\n3,2,1", Ok(("0", "2"))),
+            (5, "1|2\n\n7", Ok(("7", "0"))),
+            (
+                5,
+                "1|2\n\n1,2",
+                Err(InputError::EvenUpdate { line: 3, pages: 2 }),
+            ),
+            (
+                5,
+                "1|2|3",
+                Err(InputError::malformed(
+                    1,
+                    "`2|3` is not a valid i32 for `after`",
+                )),
+            ),
+            (
+                5,
+                "1|x",
+                Err(InputError::malformed(
+                    1,
+                    "`x` is not a valid i32 for `after`",
+                )),
+            ),
+            (5, "\n7,8,9", Ok(("8", "0"))),
+            (
+                5,
+                "1|2\n2|3\n3|1\n\n1,2,3",
+                Err(InputError::CyclicRules {
+                    pages: vec![1, 2, 3],
+                }),
+            ),
+            (
+                5,
+                "1|2\n2|3\n3|2\n\n2,1,3",
+                Err(InputError::CyclicRules { pages: vec![2, 3] }),
+            ),
+            (
+                5,
+                "1|2\n\n1|2|3",
+                Err(InputError::malformed(3, "`1|2|3` is not a page")),
+            ),
+            (
+                5,
+                "1|2\n\n1,,3",
+                Err(InputError::malformed(3, "missing a page between commas")),
+            ),
+            (
+                5,
+                "1|2\n\n3\n\n4",
+                Err(InputError::malformed(5, "expected only rules and updates")),
+            ),
+        ];
+
+        for day in 1..=5 {
+            for empty in ["", "\n", "\n\n \n"] {
+                assert_eq!(
+                    Ok(("0".to_string(), "0".to_string())),
+                    answers(day, empty),
+                    "day {day} on {empty:?}"
+                );
+            }
+        }
+
+        for (day, input, expected) in cases {
+            let expected = expected
+                .clone()
+                .map(|(a, b)| (a.to_string(), b.to_string()));
+            assert_eq!(expected, answers(*day, input), "day {day} on {input:?}");
+        }
+    }
+
+    #[test]
+    fn test_normalize() {
+        assert!(matches!(normalize("1 2\n3 4\n"), Cow::Borrowed(_)));
+        assert!(matches!(normalize(""), Cow::Borrowed(_)));
+        assert_eq!("1 2\n3 4\n", normalize("\u{feff}1\t2\r\n3 4  \r\n\r\n\n"));
+        assert_eq!("47|53\n\n1,2,3\n", normalize("47|53\r\n\r\n1,2,3"));
+        assert_eq!("", normalize("\n \n"));
+    }
+
+    #[test]
+    fn test_mangled_inputs() {
+        let mangle = |input: &str| {
+            let lines = input
+                .lines()
+                .map(|l| format!("{}\t \r\n", l.replacen(' ', "\t", 1)))
+                .collect::<String>();
+
+            format!("\u{feff}{lines}\r\n\n")
+        };
+
+        for day in 1..=5 {
+            let input = generate(find(2024, day).unwrap(), 3, 50);
+            assert_eq!(
+                answers(day, &input),
+                answers(day, &mangle(&input)),
+                "day {day}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_embedded() {
+        let input = Input::embedded("2024/1.txt").unwrap();
+        assert!(input.is_borrowed());
+        assert_eq!(1000, input.lines().count());
+        assert!(Input::embedded("2024/26.txt").is_err());
+
+        // the real day 3 input has trailing spaces to trim
+        assert!(!Input::embedded("2024/3.txt").unwrap().is_borrowed());
+    }
+
+    #[test]
+    fn test_sections() {
+        let input = Input::new("1|2\n3|4\n\n\n5,6,7\n\n8\n");
+        assert_eq!(
+            vec![(0, "1|2\n3|4"), (4, "5,6,7"), (6, "8")],
+            input.sections().collect::<Vec<_>>()
+        );
+        assert_eq!(0, Input::new("\n\n").sections().count());
+    }
+
+    #[test]
+    fn test_ints() {
+        assert_eq!(Ok(vec![3, -4]), ints::<i32>("  3   -4 ", ' '));
+        assert_eq!(Ok(vec![1, 2, 3]), ints::<i32>("1, 2 ,3", ','));
+        assert_eq!(Err("2|3"), ints::<i32>("1,2|3", ','));
+        assert_eq!(Err("1,2"), ints::<i32>("1,2 3", ' '));
+        assert_eq!(Err(""), ints::<i32>("1,,3", ','));
+        assert_eq!(Err("-1"), ints::<u8>("-1", ' '));
+        assert_eq!(Ok(vec![]), ints::<i32>("", ' '));
+    }
+
+    #[test]
+    fn test_grid() {
+        let grid = Input::new("ab\ncd\n\nef").grid().unwrap();
+        assert_eq!((2, 3), (grid.width(), grid.height()));
+        assert_eq!("ab\ncd\nef\n", grid.to_string());
+        assert!(Input::new("").grid().unwrap().is_empty());
+        assert_eq!(
+            Err(InputError::Ragged {
+                line: 2,
+                expected: 3,
+                found: 1
+            }),
+            Input::new("abc\nd").grid()
+        );
+    }
+
+    #[derive(Debug, PartialEq, FromLine)]
+    #[line("p=<{x}, {y}> {name}")]
+    struct Point {
+        x: i64,
+        y: i64,
+        name: String,
+    }
+
+    #[test]
+    fn test_from_line() {
+        assert_eq!(
+            Ok(Point {
+                x: -3,
+                y: 14,
+                name: "a b".to_string()
+            }),
+            Point::from_line("  p=<-3,   14>  a b ")
+        );
+        assert_eq!(
+            Err("expected a line like `p=<{x}, {y}> {name}`".to_string()),
+            Point::from_line("p=<-3 14> a")
+        );
+        assert_eq!(
+            Err("`1.5` is not a valid i64 for `y`".to_string()),
+            Point::from_line("p=<1, 1.5> a")
+        );
+        assert_eq!(
+            Err("missing `x`".to_string()),
+            Point::from_line("p=<, 1> a")
+        );
+        assert_eq!(
+            Err("`x` is not a valid Ipv4Addr for `ip`".to_string()),
+            field::<std::net::Ipv4Addr>("x", "ip")
+        );
+        assert_eq!(
+            "Vec<(String, &str)>",
+            short_type_name::<Vec<(String, &str)>>()
+        );
+    }
+
+    #[test]
+    fn test_signed_fields() {
+        #[derive(FromLine, Debug, PartialEq)]
+        #[line("{low}-{high}")]
+        struct Range {
+            low: i32,
+            high: i32,
+        }
+
+        let range = |low, high| Ok(Range { low, high });
+        assert_eq!(range(1, 2), Range::from_line("1-2"));
+        assert_eq!(range(-1, 2), Range::from_line("-1-2"));
+        assert_eq!(range(-1, -2), Range::from_line(" -1--2"));
+        assert_eq!(range(1, 2), Range::from_line("+1-+2"));
+        assert_eq!(
+            Err("expected a line like `{low}-{high}`".to_string()),
+            Range::from_line("-1")
+        );
+    }
+}
@@ -0,0 +1,301 @@
+use std::{
+    io::Read,
+    os::unix::process::{CommandExt, ExitStatusExt},
+    path::Path,
+    process::{Child, Command, ExitStatus, Stdio},
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    runner::{Outcome, Run},
+    solution::Solution,
+};
+
+/// Subcommand of the `aoc` binary that solves a single day and prints the
+/// resulting [`Run`] as JSON on stdout.
+pub const WORKER_COMMAND: &str = "worker";
+
+#[derive(Debug, Clone, Default)]
+pub struct Limits {
+    /// Address space limit in bytes (`RLIMIT_AS`).
+    pub memory: Option<u64>,
+    /// CPU time limit in seconds (`RLIMIT_CPU`).
+    pub cpu: Option<u64>,
+    /// Wall-clock limit after which the child is killed.
+    pub timeout: Option<Duration>,
+}
+
+/// Solves `solution` in a child process of `exe` constrained by `limits`.
+pub fn run(exe: &Path, solution: &Solution, limits: &Limits) -> Outcome {
+    let mut command = Command::new(exe);
+    command.args([
+        WORKER_COMMAND,
+        &solution.year.to_string(),
+        &solution.day.to_string(),
+    ]);
+
+    supervise(solution.name(), command, limits)
+}
+
+/// Runs `command` under `limits` and reads its outcome like a worker's.
+fn supervise(name: String, mut command: Command, limits: &Limits) -> Outcome {
+    command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+
+    let (memory, cpu) = (limits.memory, limits.cpu);
+    unsafe {
+        command.pre_exec(move || {
+            if let Some(memory) = memory {
+                let limit = libc::rlimit {
+                    rlim_cur: memory as libc::rlim_t,
+                    rlim_max: memory as libc::rlim_t,
+                };
+                if libc::setrlimit(libc::RLIMIT_AS, &limit) != 0 {
+                    return Err(std::io::Error::last_os_error());
+                }
+            }
+            if let Some(cpu) = cpu {
+                // The soft limit raises SIGXCPU, the hard one a second later SIGKILL.
+                let limit = libc::rlimit {
+                    rlim_cur: cpu as libc::rlim_t,
+                    rlim_max: (cpu + 1) as libc::rlim_t,
+                };
+                if libc::setrlimit(libc::RLIMIT_CPU, &limit) != 0 {
+                    return Err(std::io::Error::last_os_error());
+                }
+            }
+
+            Ok(())
+        });
+    }
+
+    let mut child = match command.spawn() {
+        Ok(child) => child,
+        Err(e) => {
+            return Outcome::Crashed {
+                name,
+                reason: format!("spawn failed: {e}"),
+            };
+        }
+    };
+
+    let stdout = drain(child.stdout.take());
+    let stderr = drain(child.stderr.take());
+
+    let (status, cpu) = match wait(&mut child, limits.timeout) {
+        Ok(Some(exit)) => exit,
+        Ok(None) => {
+            return Outcome::TimedOut {
+                name,
+                limit: limits.timeout.unwrap_or_default(),
+            };
+        }
+        Err(e) => {
+            return Outcome::Crashed {
+                name,
+                reason: format!("wait failed: {e}"),
+            };
+        }
+    };
+
+    let stdout = stdout.join().unwrap_or_default();
+    let stderr = stderr.join().unwrap_or_default();
+
+    // SIGKILL also comes from the OOM killer or a user, it only means the
+    // hard CPU limit once the soft one has been used up
+    let over_cpu = limits
+        .cpu
+        .is_some_and(|limit| cpu >= Duration::from_secs(limit));
+
+    classify(name, status, over_cpu, &stdout, &stderr)
+}
+
+fn drain(pipe: Option<impl Read + Send + 'static>) -> thread::JoinHandle<String> {
+    thread::spawn(move || {
+        let mut buf = String::new();
+        if let Some(mut pipe) = pipe {
+            let _ = pipe.read_to_string(&mut buf);
+        }
+
+        buf
+    })
+}
+
+/// Waits for `child` and returns how it ended with the CPU time it used,
+/// killing it and returning `None` once `timeout` elapses.
+fn wait(
+    child: &mut Child,
+    timeout: Option<Duration>,
+) -> std::io::Result<Option<(ExitStatus, Duration)>> {
+    let deadline = timeout.map(|t| Instant::now() + t);
+    let pid = child.id() as libc::pid_t;
+    loop {
+        let mut status = 0;
+        let mut usage = unsafe { std::mem::zeroed::<libc::rusage>() };
+        let flags = if deadline.is_some() { libc::WNOHANG } else { 0 };
+        // wait4 rather than try_wait to get the child's own resource usage
+        match unsafe { libc::wait4(pid, &mut status, flags, &mut usage) } {
+            -1 => {
+                let e = std::io::Error::last_os_error();
+                if e.kind() != std::io::ErrorKind::Interrupted {
+                    return Err(e);
+                }
+            }
+            0 => {}
+            _ => {
+                let cpu = |t: libc::timeval| {
+                    Duration::from_secs(t.tv_sec as u64) + Duration::from_micros(t.tv_usec as u64)
+                };
+                let used = cpu(usage.ru_utime) + cpu(usage.ru_stime);
+
+                return Ok(Some((ExitStatus::from_raw(status), used)));
+            }
+        }
+
+        if deadline.is_some_and(|d| Instant::now() >= d) {
+            child.kill()?;
+            child.wait()?;
+            return Ok(None);
+        }
+
+        thread::sleep(Duration::from_millis(5));
+    }
+}
+
+fn classify(
+    name: String,
+    status: ExitStatus,
+    over_cpu: bool,
+    stdout: &str,
+    stderr: &str,
+) -> Outcome {
+    if stderr.contains("memory allocation of") {
+        return Outcome::OutOfMemory { name };
+    }
+
+    match status.signal() {
+        Some(libc::SIGXCPU) => return Outcome::CpuLimit { name },
+        Some(libc::SIGKILL) if over_cpu => return Outcome::CpuLimit { name },
+        Some(signal) => {
+            return Outcome::Crashed {
+                name,
+                reason: format!("killed by signal {signal}"),
+            };
+        }
+        None => {}
+    }
+
+    if stderr.contains("panicked") {
+        return Outcome::Panicked { name };
+    }
+
+    match serde_json::from_str::<Run>(stdout) {
+        Ok(run) if status.success() => Outcome::Done(Box::new(run)),
+        _ => Outcome::Crashed {
+            name,
+            reason: match stderr.lines().last() {
+                Some(line) => format!("{status}: {line}"),
+                None => status.to_string(),
+            },
+        },
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        os::unix::process::ExitStatusExt,
+        process::{Command, ExitStatus},
+        time::Duration,
+    };
+
+    use super::{Limits, classify, supervise};
+    use crate::{
+        context::Context,
+        runner::{Outcome, run},
+        solution::find,
+    };
+
+    #[test]
+    fn test_classify() {
+        let name = || "2024/01".to_string();
+        let ok = ExitStatus::from_raw(0);
+        let json =
+            serde_json::to_string(&run(find(2024, 1).unwrap(), &Context::default())).unwrap();
+
+        assert!(matches!(classify(name(), ok, false, &json, ""), Outcome::Done(r) if r.is_ok()));
+        assert!(matches!(
+            classify(name(), ExitStatus::from_raw(libc::SIGXCPU), false, "", ""),
+            Outcome::CpuLimit { .. }
+        ));
+        assert!(matches!(
+            classify(
+                name(),
+                ExitStatus::from_raw(libc::SIGABRT),
+                false,
+                "",
+                "memory allocation of 1024 bytes failed"
+            ),
+            Outcome::OutOfMemory { .. }
+        ));
+        assert!(matches!(
+            classify(name(), ExitStatus::from_raw(libc::SIGSEGV), false, "", ""),
+            Outcome::Crashed { .. }
+        ));
+        assert!(matches!(
+            classify(
+                name(),
+                ExitStatus::from_raw(101 << 8),
+                false,
+                "",
+                "thread 'main' panicked"
+            ),
+            Outcome::Panicked { .. }
+        ));
+    }
+
+    #[test]
+    fn test_sigkill() {
+        let name = || "2024/01".to_string();
+        let killed = ExitStatus::from_raw(libc::SIGKILL);
+        assert!(matches!(
+            classify(name(), killed, true, "", ""),
+            Outcome::CpuLimit { .. }
+        ));
+        assert!(matches!(
+            classify(name(), killed, false, "", ""),
+            Outcome::Crashed { .. }
+        ));
+    }
+
+    fn shell(script: &str) -> Command {
+        let mut command = Command::new("sh");
+        command.args(["-c", script]);
+        command
+    }
+
+    #[test]
+    fn test_limits() {
+        let cpu = Limits {
+            cpu: Some(1),
+            timeout: Some(Duration::from_secs(30)),
+            ..Limits::default()
+        };
+        let outcome = supervise("busy".to_string(), shell("while :; do :; done"), &cpu);
+        assert!(matches!(outcome, Outcome::CpuLimit { .. }), "{outcome:?}");
+
+        // killed before using any of its CPU time
+        let outcome = supervise("killed".to_string(), shell("kill -9 $$"), &cpu);
+        assert!(matches!(outcome, Outcome::Crashed { .. }), "{outcome:?}");
+
+        let wall = Limits {
+            timeout: Some(Duration::from_millis(100)),
+            ..Limits::default()
+        };
+        let outcome = supervise("sleepy".to_string(), shell("sleep 5"), &wall);
+        assert!(matches!(outcome, Outcome::TimedOut { .. }), "{outcome:?}");
+    }
+}
@@ -1,36 +1,34 @@
 #![allow(dead_code)]
 
-use std::{
-    borrow::Cow,
-    io::{BufReader, Cursor},
-};
-
-use anyhow::Ok;
+// lets `#[derive(FromLine)]` name `::aoc` from inside this crate too
+extern crate self as aoc;
 
+pub mod alloc;
 pub mod aoc2024;
+pub mod bench_history;
+pub mod budget;
+pub mod combinator;
+pub mod complexity;
+pub mod context;
+pub mod differential;
+pub mod generate;
+pub mod geometry;
+pub mod grid;
+pub mod input;
+#[cfg(unix)]
+pub mod isolate;
+pub mod repl;
+pub mod runner;
+pub mod shrink;
+pub mod solution;
+#[cfg(test)]
+mod strategy;
+pub mod visualize;
+
+#[cfg(feature = "alloc-stats")]
+#[global_allocator]
+static GLOBAL: alloc::CountingAllocator = alloc::CountingAllocator;
 
 #[derive(rust_embed::Embed)]
 #[folder = "$CARGO_MANIFEST_DIR/resources"]
 pub(crate) struct Asset;
-
-pub(crate) fn read_input(file_path: &str) -> anyhow::Result<BufReader<Cursor<Cow<'static, [u8]>>>> {
-    if let Some(asset) = Asset::get(file_path) {
-        let cursor = Cursor::new(asset.data);
-        let reader = BufReader::new(cursor);
-
-        return Ok(reader);
-    }
-
-    anyhow::bail!("no asset")
-}
-
-#[cfg(test)]
-mod test {
-    use crate::read_input;
-
-    #[test]
-    fn test_read() {
-        let reader = read_input("2024/1.txt").unwrap();
-        assert!(reader.capacity() != 0);
-    }
-}
@@ -0,0 +1,542 @@
+use std::{
+    io::{IsTerminal, Write},
+    path::PathBuf,
+    process::ExitCode,
+    sync::{Arc, Mutex},
+    thread,
+    time::Duration,
+};
+
+use aoc::{
+    alloc, bench_history, complexity,
+    context::{self, Context},
+    differential, generate, runner, solution,
+    visualize::export,
+};
+use clap::{Args, Parser, Subcommand};
+use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
+
+#[derive(Parser)]
+#[command(name = "aoc", about = "Advent of Code solutions")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Solve puzzles and print a timing table
+    Run(RunArgs),
+    /// Animate how a day reaches its answer
+    Viz {
+        year: u16,
+        day: u8,
+        /// Frames per second between 0.5 and 240, `+` and `-` change it while playing
+        #[arg(long, default_value_t = 10.0)]
+        fps: f64,
+        #[command(flatten)]
+        export: ExportArgs,
+    },
+    /// Explore the parsed state of a day interactively
+    Repl { year: u16, day: u8 },
+    /// Check a day's alternative implementations against each other
+    Diff {
+        year: u16,
+        day: u8,
+        /// Input file to check instead of the embedded one
+        #[arg(long)]
+        input: Option<PathBuf>,
+        /// Shrink the first disagreement and write the minimal input here
+        #[arg(long)]
+        shrink: Option<PathBuf>,
+    },
+    /// Time each part on generated inputs of growing size and estimate its complexity
+    Scale {
+        year: u16,
+        day: Option<u8>,
+        /// Generator size of the smallest input, see `gen`; days 2 and 5
+        /// make their lines longer rather than more numerous
+        #[arg(long, default_value_t = 100)]
+        start: usize,
+        /// Number of sizes, each double the previous one
+        #[arg(long, default_value_t = 5)]
+        steps: u32,
+        /// Runs per size, the median is reported
+        #[arg(long, default_value_t = 5)]
+        repeats: usize,
+        #[arg(long, default_value_t = 0)]
+        seed: u64,
+    },
+    /// Print a random input for a day
+    Gen {
+        year: u16,
+        day: u8,
+        /// Lines, reports, bytes, grid side or pages depending on the day
+        #[arg(long, default_value_t = 1000)]
+        size: usize,
+        #[arg(long, default_value_t = 0)]
+        seed: u64,
+        /// Write the input here instead of stdout
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+    },
+    /// Solve a single day and print the result as JSON, used by `run --isolate`
+    #[command(hide = true)]
+    Worker { year: u16, day: u8 },
+    /// Track criterion results across commits
+    Bench {
+        #[command(subcommand)]
+        command: BenchCommand,
+    },
+}
+
+#[derive(Args)]
+struct RunArgs {
+    year: Option<u16>,
+    day: Option<u8>,
+    /// Number of days solved concurrently, defaults to one per CPU
+    /// (or one with `alloc-stats`, whose counters are process wide)
+    #[arg(long, short)]
+    jobs: Option<usize>,
+    /// Give up on a day after this many seconds
+    /// (only with --isolate under `alloc-stats`, as a timed out day keeps running)
+    #[arg(long, value_parser = seconds)]
+    timeout: Option<Duration>,
+    /// Solve each day in its own child process
+    #[arg(long)]
+    isolate: bool,
+    /// Address space limit per isolated day, in MiB
+    #[arg(long, requires = "isolate")]
+    memory_limit: Option<u64>,
+    /// CPU time limit per isolated day, in seconds
+    #[arg(long, requires = "isolate")]
+    cpu_limit: Option<u64>,
+    /// Write spans for input loading, parsing and each part as a Chrome trace
+    #[arg(long, conflicts_with = "isolate")]
+    trace: Option<PathBuf>,
+}
+
+#[derive(Args)]
+struct ExportArgs {
+    /// Write frames to an .svg, .png or animated .gif file instead of playing them
+    #[arg(long)]
+    export: Option<PathBuf>,
+    /// Frame to export as a still image, defaults to the last one
+    #[arg(long, conflicts_with = "all")]
+    frame: Option<usize>,
+    /// Export every frame as `<name>-0001.<ext>`
+    #[arg(long)]
+    all: bool,
+    /// Size of a grid cell in pixels
+    #[arg(long, default_value_t = 12)]
+    cell: u32,
+}
+
+#[derive(Subcommand)]
+enum BenchCommand {
+    /// Append the latest `cargo bench` results to the history file
+    Record {
+        #[arg(long, default_value = "target/criterion")]
+        criterion_dir: PathBuf,
+        #[arg(long, default_value = ".bench-history.jsonl")]
+        history: PathBuf,
+    },
+    /// Compare the latest `cargo bench` results against the previous baseline
+    Compare {
+        #[arg(long, default_value = "target/criterion")]
+        criterion_dir: PathBuf,
+        #[arg(long, default_value = ".bench-history.jsonl")]
+        history: PathBuf,
+        /// Allowed slowdown in percent before a benchmark counts as regressed
+        #[arg(long, default_value_t = 10.0)]
+        threshold: f64,
+        /// Also record the current results once compared
+        #[arg(long)]
+        record: bool,
+    },
+}
+
+fn main() -> anyhow::Result<ExitCode> {
+    let cli = Cli::parse();
+
+    match cli.command {
+        Command::Run(args) => run(args),
+        Command::Viz {
+            year,
+            day,
+            fps,
+            export,
+        } => viz(year, day, fps, export),
+        Command::Repl { year, day } => repl(year, day),
+        Command::Diff {
+            year,
+            day,
+            input,
+            shrink,
+        } => diff(year, day, input, shrink),
+        Command::Scale {
+            year,
+            day,
+            start,
+            steps,
+            repeats,
+            seed,
+        } => scale(year, day, start, steps, repeats, seed),
+        Command::Gen {
+            year,
+            day,
+            size,
+            seed,
+            output,
+        } => gen_input(year, day, size, seed, output),
+        Command::Worker { year, day } => worker(year, day),
+        Command::Bench { command } => bench(command),
+    }
+}
+
+fn run(args: RunArgs) -> anyhow::Result<ExitCode> {
+    // flushes the trace file when dropped at the end of the run
+    let _trace = args.trace.as_ref().map(|path| {
+        let (layer, guard) = tracing_chrome::ChromeLayerBuilder::new()
+            .file(path)
+            .include_args(true)
+            .build();
+        tracing_subscriber::registry().with(layer).init();
+
+        guard
+    });
+
+    let solutions = solution::select(args.year, args.day);
+    if solutions.is_empty() {
+        anyhow::bail!("no solution found")
+    }
+
+    let jobs = args.jobs.unwrap_or_else(|| {
+        if alloc::enabled() {
+            1
+        } else {
+            thread::available_parallelism().map_or(1, |n| n.get())
+        }
+    });
+    if alloc::enabled() && jobs > 1 && !args.isolate {
+        anyhow::bail!(
+            "alloc-stats counts every thread together, use --jobs 1 or --isolate instead of --jobs {jobs}"
+        )
+    }
+    if alloc::enabled() && args.timeout.is_some() && !args.isolate {
+        // a timed out day may keep allocating on its thread while later days run
+        anyhow::bail!("alloc-stats can't tell a timed out day from the next ones, add --isolate")
+    }
+    let timeout = args.timeout;
+
+    let outcomes = if args.isolate {
+        isolated(&solutions, jobs, timeout, &args)?
+    } else {
+        runner::run_all(&solutions, jobs, |s| {
+            runner::run_with_timeout(s, timeout, &progress_context())
+        })
+    };
+    if std::io::stderr().is_terminal() {
+        eprint!("\r\x1b[2K");
+    }
+    print!("{}", runner::table(&outcomes));
+
+    let timed_out = outcomes
+        .iter()
+        .filter(|o| matches!(o, runner::Outcome::TimedOut { .. }))
+        .map(|o| o.name())
+        .collect::<Vec<_>>();
+    if !timed_out.is_empty() {
+        eprintln!("timed out: {}", timed_out.join(", "));
+    }
+
+    if outcomes.iter().all(|o| o.is_ok()) {
+        Ok(ExitCode::SUCCESS)
+    } else {
+        Ok(ExitCode::FAILURE)
+    }
+}
+
+/// Reads a duration given in seconds, which may have a fraction.
+fn seconds(s: &str) -> Result<Duration, String> {
+    s.parse::<f64>()
+        .ok()
+        .and_then(|secs| Duration::try_from_secs_f64(secs).ok())
+        .ok_or_else(|| format!("`{s}` is not a number of seconds"))
+}
+
+/// A context rendering a progress bar on stderr when it is a terminal.
+fn progress_context() -> Context {
+    if !std::io::stderr().is_terminal() {
+        return Context::default();
+    }
+
+    let last = Arc::new(Mutex::new((String::new(), usize::MAX)));
+    Context::default().with_progress(Arc::new(move |label, done, total| {
+        let percent = (done * 100).checked_div(total).unwrap_or(100);
+        let mut last = last.lock().unwrap();
+        if last.0 == label && last.1 == percent {
+            return;
+        }
+        *last = (label.to_string(), percent);
+
+        let mut stderr = std::io::stderr().lock();
+        let _ = write!(
+            stderr,
+            "\r\x1b[2K{}",
+            context::progress_bar(label, done, total, 40)
+        );
+        let _ = stderr.flush();
+    }))
+}
+
+#[cfg(unix)]
+fn isolated(
+    solutions: &[&'static solution::Solution],
+    jobs: usize,
+    timeout: Option<Duration>,
+    args: &RunArgs,
+) -> anyhow::Result<Vec<runner::Outcome>> {
+    let exe = std::env::current_exe()?;
+    let limits = aoc::isolate::Limits {
+        memory: args.memory_limit.map(|m| m << 20),
+        cpu: args.cpu_limit,
+        timeout,
+    };
+
+    Ok(runner::run_all(solutions, jobs, |s| {
+        aoc::isolate::run(&exe, s, &limits)
+    }))
+}
+
+#[cfg(not(unix))]
+fn isolated(
+    _: &[&'static solution::Solution],
+    _: usize,
+    _: Option<Duration>,
+    _: &RunArgs,
+) -> anyhow::Result<Vec<runner::Outcome>> {
+    anyhow::bail!("--isolate is only supported on unix")
+}
+
+fn viz(year: u16, day: u8, fps: f64, export: ExportArgs) -> anyhow::Result<ExitCode> {
+    let Some(solution) = solution::find(year, day) else {
+        anyhow::bail!("no solution for {year}/{day:02}")
+    };
+
+    let solver = (solution.parse)()?;
+    let Some(visualize) = solver.visualize() else {
+        anyhow::bail!("{} has no visualization", solution.name())
+    };
+
+    let Some(path) = export.export else {
+        aoc::visualize::terminal::play(visualize.frames(), fps)?;
+        return Ok(ExitCode::SUCCESS);
+    };
+
+    let ext = path
+        .extension()
+        .and_then(|s| s.to_str())
+        .unwrap_or_default();
+    let count = match ext {
+        "gif" => export::write_gif(visualize.frames(), export.cell, fps, &path)?,
+        _ if export.all => export::write_sequence(visualize.frames(), export.cell, &path)?,
+        "svg" | "png" => {
+            let frame = match export.frame {
+                Some(n) => visualize.frames().nth(n),
+                None => visualize.frames().last(),
+            };
+            let Some(frame) = frame else {
+                anyhow::bail!("no such frame")
+            };
+
+            if ext == "svg" {
+                export::write_svg(&frame, export.cell, &path)?;
+            } else {
+                export::write_png(&frame, export.cell, &path)?;
+            }
+            1
+        }
+        _ => anyhow::bail!("unsupported export format `{ext}`, use svg, png or gif"),
+    };
+    println!("wrote {count} frame(s) to {}", path.display());
+
+    Ok(ExitCode::SUCCESS)
+}
+
+fn repl(year: u16, day: u8) -> anyhow::Result<ExitCode> {
+    let Some(solution) = solution::find(year, day) else {
+        anyhow::bail!("no solution for {year}/{day:02}")
+    };
+
+    let stdin = std::io::stdin();
+    let prompt = stdin.is_terminal();
+    aoc::repl::Repl::new(solution)?.run(stdin.lock(), std::io::stdout(), prompt)?;
+
+    Ok(ExitCode::SUCCESS)
+}
+
+fn diff(
+    year: u16,
+    day: u8,
+    input: Option<PathBuf>,
+    shrink: Option<PathBuf>,
+) -> anyhow::Result<ExitCode> {
+    let Some(solution) = solution::find(year, day) else {
+        anyhow::bail!("no solution for {year}/{day:02}")
+    };
+    if solution.variants.is_empty() {
+        anyhow::bail!("{} has no variants to compare", solution.name())
+    }
+
+    let (label, text) = match &input {
+        Some(path) => (path.display().to_string(), std::fs::read_to_string(path)?),
+        None => (
+            "embedded input".to_string(),
+            solution.input()?.as_str().to_string(),
+        ),
+    };
+
+    let disagreements = differential::check(solution, &label, &text)?;
+    for d in &disagreements {
+        println!("{d}");
+    }
+    let Some(first) = disagreements.first() else {
+        println!("{} variant(s) agree on {label}", solution.variants.len());
+        return Ok(ExitCode::SUCCESS);
+    };
+
+    if let Some(path) = shrink {
+        let minimal = differential::minimize(solution, first, &text);
+        std::fs::write(&path, &minimal)?;
+        println!(
+            "wrote a {}-line reproduction to {}",
+            minimal.lines().count(),
+            path.display()
+        );
+    }
+
+    Ok(ExitCode::FAILURE)
+}
+
+fn scale(
+    year: u16,
+    day: Option<u8>,
+    start: usize,
+    steps: u32,
+    repeats: usize,
+    seed: u64,
+) -> anyhow::Result<ExitCode> {
+    let solutions = solution::select(Some(year), day);
+    if solutions.is_empty() {
+        anyhow::bail!("no solution found")
+    }
+    if cfg!(debug_assertions) {
+        eprintln!("warning: timings from a debug build, use --release");
+    }
+
+    let sizes = (0..steps).map(|i| start << i).collect::<Vec<_>>();
+    for solution in solutions {
+        let stages = complexity::measure(solution, &sizes, seed, repeats)?;
+        print!("{}", complexity::table(&solution.name(), &stages));
+    }
+
+    Ok(ExitCode::SUCCESS)
+}
+
+fn gen_input(
+    year: u16,
+    day: u8,
+    size: usize,
+    seed: u64,
+    output: Option<PathBuf>,
+) -> anyhow::Result<ExitCode> {
+    let Some(solution) = solution::find(year, day) else {
+        anyhow::bail!("no solution for {year}/{day:02}")
+    };
+
+    let input = generate::generate(solution, seed, size);
+    match output {
+        Some(path) => std::fs::write(path, input)?,
+        None => std::io::stdout().lock().write_all(input.as_bytes())?,
+    }
+
+    Ok(ExitCode::SUCCESS)
+}
+
+fn worker(year: u16, day: u8) -> anyhow::Result<ExitCode> {
+    let Some(solution) = solution::find(year, day) else {
+        anyhow::bail!("no solution for {year}/{day:02}")
+    };
+
+    println!(
+        "{}",
+        serde_json::to_string(&runner::run(solution, &Context::default()))?
+    );
+
+    Ok(ExitCode::SUCCESS)
+}
+
+fn bench(command: BenchCommand) -> anyhow::Result<ExitCode> {
+    match command {
+        BenchCommand::Record {
+            criterion_dir,
+            history,
+        } => {
+            let results = bench_history::collect(&criterion_dir)?;
+            let entry = bench_history::Entry::new(bench_history::current_commit()?, results);
+            bench_history::append(&history, &entry)?;
+            println!(
+                "recorded {} benchmarks for {}",
+                entry.results.len(),
+                entry.commit
+            );
+
+            Ok(ExitCode::SUCCESS)
+        }
+        BenchCommand::Compare {
+            criterion_dir,
+            history,
+            threshold,
+            record,
+        } => {
+            let results = bench_history::collect(&criterion_dir)?;
+            let commit = bench_history::current_commit()?;
+            let entries = bench_history::load(&history)?;
+
+            let mut regressed = 0;
+            if let Some(baseline) = bench_history::baseline(&entries, &commit) {
+                println!("baseline {}", baseline.commit);
+                for c in bench_history::compare(baseline, &results) {
+                    let flag = if c.is_regression(threshold) {
+                        regressed += 1;
+                        "REGRESSED"
+                    } else {
+                        ""
+                    };
+                    println!(
+                        "{:<50} {:>14.1} ns {:>14.1} ns {:>+8.2}% {}",
+                        c.id,
+                        c.baseline,
+                        c.current,
+                        c.change(),
+                        flag
+                    );
+                }
+            } else {
+                println!("no baseline in {}", history.display());
+            }
+
+            if record {
+                bench_history::append(&history, &bench_history::Entry::new(commit, results))?;
+            }
+
+            if regressed > 0 {
+                eprintln!("{regressed} benchmark(s) regressed beyond {threshold}%");
+                return Ok(ExitCode::FAILURE);
+            }
+
+            Ok(ExitCode::SUCCESS)
+        }
+    }
+}
@@ -0,0 +1,212 @@
+use std::{
+    fmt::Write as _,
+    fs,
+    io::{BufRead, Write},
+    time::Instant,
+};
+
+use crate::{
+    context::Context,
+    solution::{Solution, Solve},
+};
+
+const COMMANDS: &[(&str, &str)] = &[
+    ("part1", "solve part one"),
+    ("part2", "solve part two"),
+    (
+        "load [path]",
+        "parse another input, or the embedded one without a path",
+    ),
+    ("time <command>", "run a command and show how long it took"),
+    ("help", "show this help"),
+    ("quit", "leave the repl"),
+];
+
+enum Flow {
+    Continue(String),
+    Quit,
+}
+
+/// Interactive session over the parsed state of a single day.
+pub struct Repl {
+    solution: &'static Solution,
+    solver: Box<dyn Solve>,
+    input: String,
+}
+
+impl Repl {
+    pub fn new(solution: &'static Solution) -> anyhow::Result<Self> {
+        Ok(Self {
+            solution,
+            solver: (solution.parse)()?,
+            input: "embedded input".to_string(),
+        })
+    }
+
+    /// Reads commands from `input` until it ends or `quit`, writing results to `output`.
+    pub fn run(
+        &mut self,
+        input: impl BufRead,
+        mut output: impl Write,
+        prompt: bool,
+    ) -> anyhow::Result<()> {
+        let name = self.solution.name();
+        if prompt {
+            write!(output, "{name}> ")?;
+            output.flush()?;
+        }
+
+        for line in input.lines() {
+            let line = line?;
+            match self.execute(line.trim()) {
+                Ok(Flow::Quit) => break,
+                Ok(Flow::Continue(s)) if s.is_empty() => {}
+                Ok(Flow::Continue(s)) => writeln!(output, "{s}")?,
+                Err(e) => writeln!(output, "error: {e:#}")?,
+            }
+
+            if prompt {
+                write!(output, "{name}> ")?;
+                output.flush()?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn execute(&mut self, line: &str) -> anyhow::Result<Flow> {
+        let mut words = line.split_whitespace();
+        let Some(command) = words.next() else {
+            return Ok(Flow::Continue(String::new()));
+        };
+        let args = words.collect::<Vec<_>>();
+
+        let out = match command {
+            "quit" | "exit" => return Ok(Flow::Quit),
+            "help" => self.help(),
+            "part1" => self.solver.part_one(&Context::default())?,
+            "part2" => self.solver.part_two(&Context::default())?,
+            "load" => self.load(args.first().copied())?,
+            "time" => {
+                let start = Instant::now();
+                let flow = self.execute(&args.join(" "))?;
+                let elapsed = start.elapsed();
+
+                match flow {
+                    Flow::Quit => return Ok(Flow::Quit),
+                    Flow::Continue(s) if s.is_empty() => format!("{elapsed:.2?}"),
+                    Flow::Continue(s) => format!("{s}\n{elapsed:.2?}"),
+                }
+            }
+            _ => match self.solver.query(command, &args) {
+                Some(res) => res?,
+                None => anyhow::bail!("unknown command `{command}`, try `help`"),
+            },
+        };
+
+        Ok(Flow::Continue(out))
+    }
+
+    fn load(&mut self, path: Option<&str>) -> anyhow::Result<String> {
+        let start = Instant::now();
+        let (solver, input) = match path {
+            Some(path) => {
+                let data = fs::read_to_string(path)?;
+                ((self.solution.parse_input)(&data)?, path.to_string())
+            }
+            None => ((self.solution.parse)()?, "embedded input".to_string()),
+        };
+        let elapsed = start.elapsed();
+
+        self.solver = solver;
+        self.input = input;
+
+        Ok(format!("parsed {} in {elapsed:.2?}", self.input))
+    }
+
+    fn help(&self) -> String {
+        let mut out = format!("{} using {}\n", self.solution.name(), self.input);
+        for (usage, description) in COMMANDS.iter().chain(self.solver.queries()) {
+            let _ = writeln!(out, "  {usage:<20} {description}");
+        }
+        out.pop();
+
+        out
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        env, fs,
+        path::PathBuf,
+        process,
+        sync::atomic::{AtomicUsize, Ordering},
+    };
+
+    use super::Repl;
+    use crate::solution::find;
+
+    fn session(day: u8, commands: &str) -> Vec<String> {
+        let mut repl = Repl::new(find(2024, day).unwrap()).unwrap();
+        let mut output = vec![];
+        repl.run(commands.as_bytes(), &mut output, false).unwrap();
+
+        String::from_utf8(output)
+            .unwrap()
+            .lines()
+            .map(String::from)
+            .collect()
+    }
+
+    #[test]
+    fn test_session() {
+        let out = session(
+            4,
+            "size\ncell 0 0\npart2\nbogus\ncell 1000 0\nquit\npart1\n",
+        );
+        assert_eq!("140 140", out[0]);
+        assert_eq!("S", out[1]);
+        assert_eq!("1902", out[2]);
+        assert!(out[3].starts_with("error: unknown command"));
+        assert!(out[4].starts_with("error: 1000 0 is out of bounds"));
+        assert_eq!(5, out.len());
+    }
+
+    /// A file in the temp directory unique to this test run, removed on drop.
+    struct TempFile(PathBuf);
+
+    impl TempFile {
+        fn new(contents: &str) -> Self {
+            static COUNTER: AtomicUsize = AtomicUsize::new(0);
+            let n = COUNTER.fetch_add(1, Ordering::Relaxed);
+            let path = env::temp_dir().join(format!("aoc_repl_{}_{n}.txt", process::id()));
+            fs::write(&path, contents).unwrap();
+
+            Self(path)
+        }
+    }
+
+    impl Drop for TempFile {
+        fn drop(&mut self) {
+            let _ = fs::remove_file(&self.0);
+        }
+    }
+
+    #[test]
+    fn test_time_and_load() {
+        let file = TempFile::new("47|53\n97|47\n97|53\n\n97,47,53\n53,47,97\n");
+
+        let commands = format!(
+            "time part1\nload {}\nrules 47\nupdate 1\npart1\npart2\n",
+            file.0.display()
+        );
+        let out = session(5, &commands);
+        assert_eq!("4569", out[0]);
+        assert!(out[2].starts_with("parsed "));
+        assert_eq!("[53]", out[3]);
+        assert_eq!("[53, 47, 97] sorted: [97, 47, 53]", out[4]);
+        assert_eq!("47", out[5]);
+        assert_eq!("47", out[6]);
+    }
+}
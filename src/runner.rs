@@ -0,0 +1,366 @@
+use std::{
+    fmt::Write,
+    panic::{self, AssertUnwindSafe},
+    sync::{
+        Mutex,
+        mpsc::{self, RecvTimeoutError},
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    alloc::{self, AllocStats},
+    context::Context,
+    solution::Solution,
+};
+
+/// How long a cancelled day gets to wind down after its timeout.
+const GRACE: Duration = Duration::from_millis(100);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Stage {
+    /// The answer for a part, empty for parsing, or the error message.
+    pub output: Result<String, String>,
+    pub elapsed: Duration,
+    pub alloc: Option<AllocStats>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Run {
+    pub name: String,
+    pub parse: Stage,
+    pub part_one: Option<Stage>,
+    pub part_two: Option<Stage>,
+}
+
+impl Run {
+    pub fn is_ok(&self) -> bool {
+        [
+            Some(&self.parse),
+            self.part_one.as_ref(),
+            self.part_two.as_ref(),
+        ]
+        .iter()
+        .all(|s| s.is_some_and(|s| s.output.is_ok()))
+    }
+
+    fn stages(&self) -> impl Iterator<Item = (&'static str, &Stage)> {
+        [
+            ("parse", Some(&self.parse)),
+            ("part 1", self.part_one.as_ref()),
+            ("part 2", self.part_two.as_ref()),
+        ]
+        .into_iter()
+        .filter_map(|(label, stage)| stage.map(|s| (label, s)))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum Outcome {
+    Done(Box<Run>),
+    TimedOut { name: String, limit: Duration },
+    Panicked { name: String },
+    OutOfMemory { name: String },
+    CpuLimit { name: String },
+    Crashed { name: String, reason: String },
+}
+
+impl Outcome {
+    pub fn name(&self) -> &str {
+        match self {
+            Outcome::Done(run) => &run.name,
+            Outcome::TimedOut { name, .. }
+            | Outcome::Panicked { name }
+            | Outcome::OutOfMemory { name }
+            | Outcome::CpuLimit { name }
+            | Outcome::Crashed { name, .. } => name,
+        }
+    }
+
+    pub fn is_ok(&self) -> bool {
+        matches!(self, Outcome::Done(run) if run.is_ok())
+    }
+}
+
+fn stage<T>(f: impl FnOnce() -> anyhow::Result<T>) -> (Option<T>, Stage) {
+    let start = Instant::now();
+    let (res, alloc) = alloc::measure(f);
+    let elapsed = start.elapsed();
+
+    match res {
+        Ok(v) => (
+            Some(v),
+            Stage {
+                output: Ok(String::new()),
+                elapsed,
+                alloc,
+            },
+        ),
+        Err(e) => (
+            None,
+            Stage {
+                output: Err(format!("{e:#}")),
+                elapsed,
+                alloc,
+            },
+        ),
+    }
+}
+
+pub fn run(solution: &Solution, ctx: &Context) -> Run {
+    let name = solution.name();
+    let _span = tracing::info_span!("solve", day = %name).entered();
+
+    let (solver, parse) = tracing::info_span!("parse").in_scope(|| stage(solution.parse));
+    let mut run = Run {
+        name: name.clone(),
+        parse,
+        part_one: None,
+        part_two: None,
+    };
+
+    if let Some(solver) = solver {
+        let (answer, mut part_one) = tracing::info_span!("part_one")
+            .in_scope(|| stage(|| solver.part_one(&ctx.stage(format!("{name} part 1")))));
+        if let Some(answer) = answer {
+            part_one.output = Ok(answer);
+        }
+
+        let (answer, mut part_two) = tracing::info_span!("part_two")
+            .in_scope(|| stage(|| solver.part_two(&ctx.stage(format!("{name} part 2")))));
+        if let Some(answer) = answer {
+            part_two.output = Ok(answer);
+        }
+
+        run.part_one = Some(part_one);
+        run.part_two = Some(part_two);
+    }
+
+    run
+}
+
+/// Runs `solution` on its own thread and gives up waiting after `timeout`.
+///
+/// A day that times out is cancelled through `ctx` and given a short grace
+/// period to stop; a solver that never checks its context keeps its thread
+/// until the process exits while the rest of the run carries on without it.
+pub fn run_with_timeout(
+    solution: &'static Solution,
+    timeout: Option<Duration>,
+    ctx: &Context,
+) -> Outcome {
+    let Some(limit) = timeout else {
+        // reported like a panic on the timeout thread rather than ending the run
+        return match panic::catch_unwind(AssertUnwindSafe(|| run(solution, ctx))) {
+            Ok(run) => Outcome::Done(Box::new(run)),
+            Err(_) => Outcome::Panicked {
+                name: solution.name(),
+            },
+        };
+    };
+
+    let name = solution.name();
+    let (tx, rx) = mpsc::channel();
+    let thread_ctx = ctx.clone();
+    let spawned = thread::Builder::new().name(name.clone()).spawn(move || {
+        let _ = tx.send(run(solution, &thread_ctx));
+    });
+    if let Err(e) = spawned {
+        return Outcome::Crashed {
+            name,
+            reason: format!("could not start its thread: {e}"),
+        };
+    }
+
+    match rx.recv_timeout(limit) {
+        Ok(run) => Outcome::Done(Box::new(run)),
+        Err(RecvTimeoutError::Timeout) => {
+            ctx.cancel();
+            let _ = rx.recv_timeout(GRACE);
+            Outcome::TimedOut { name, limit }
+        }
+        Err(RecvTimeoutError::Disconnected) => Outcome::Panicked { name },
+    }
+}
+
+/// Runs every solution with `execute` on a pool of `jobs` workers, keeping the input order.
+pub fn run_all(
+    solutions: &[&'static Solution],
+    jobs: usize,
+    execute: impl Fn(&'static Solution) -> Outcome + Sync,
+) -> Vec<Outcome> {
+    let queue = Mutex::new(solutions.iter().copied().enumerate());
+    let outcomes = Mutex::new(vec![None; solutions.len()]);
+
+    thread::scope(|s| {
+        for _ in 0..jobs.clamp(1, solutions.len().max(1)) {
+            s.spawn(|| {
+                loop {
+                    let Some((i, solution)) = queue.lock().unwrap().next() else {
+                        break;
+                    };
+
+                    let outcome = execute(solution);
+                    outcomes.lock().unwrap()[i] = Some(outcome);
+                }
+            });
+        }
+    });
+
+    outcomes
+        .into_inner()
+        .unwrap()
+        .into_iter()
+        .flatten()
+        .collect()
+}
+
+fn bytes(n: usize) -> String {
+    match n {
+        n if n >= 1 << 20 => format!("{:.1} MiB", n as f64 / (1 << 20) as f64),
+        n if n >= 1 << 10 => format!("{:.1} KiB", n as f64 / (1 << 10) as f64),
+        n => format!("{n} B"),
+    }
+}
+
+pub fn table(outcomes: &[Outcome]) -> String {
+    let mut out = String::new();
+    let with_alloc = alloc::enabled();
+
+    let _ = write!(
+        out,
+        "{:<8} {:<7} {:<20} {:>12}",
+        "day", "stage", "answer", "time"
+    );
+    if with_alloc {
+        let _ = write!(out, " {:>10} {:>12} {:>12}", "allocs", "bytes", "peak");
+    }
+    out.push('\n');
+
+    for outcome in outcomes {
+        let run = match outcome {
+            Outcome::Done(run) => run,
+            Outcome::TimedOut { name, limit } => {
+                let _ = writeln!(out, "{name:<8} {:<7} timed out after {limit:?}", "");
+                continue;
+            }
+            Outcome::Panicked { name } => {
+                let _ = writeln!(out, "{name:<8} {:<7} panicked", "");
+                continue;
+            }
+            Outcome::OutOfMemory { name } => {
+                let _ = writeln!(out, "{name:<8} {:<7} out of memory", "");
+                continue;
+            }
+            Outcome::CpuLimit { name } => {
+                let _ = writeln!(out, "{name:<8} {:<7} exceeded cpu limit", "");
+                continue;
+            }
+            Outcome::Crashed { name, reason } => {
+                let _ = writeln!(out, "{name:<8} {:<7} crashed: {reason}", "");
+                continue;
+            }
+        };
+
+        for (label, stage) in run.stages() {
+            let answer = match &stage.output {
+                Ok(v) => v.clone(),
+                Err(e) => format!("error: {e}"),
+            };
+            let _ = write!(
+                out,
+                "{:<8} {:<7} {:<20} {:>12}",
+                run.name,
+                label,
+                answer,
+                format!("{:.2?}", stage.elapsed)
+            );
+            if let Some(a) = stage.alloc {
+                let _ = write!(
+                    out,
+                    " {:>10} {:>12} {:>12}",
+                    a.allocations,
+                    bytes(a.bytes),
+                    bytes(a.peak)
+                );
+            }
+            out.push('\n');
+        }
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use super::{Outcome, run, run_all, run_with_timeout, table};
+    use crate::{
+        context::Context,
+        solution::{Solution, find, select},
+    };
+
+    #[test]
+    fn test_run() {
+        let run = run(find(2024, 1).unwrap(), &Context::default());
+        assert!(run.is_ok());
+        assert_eq!(Ok("2367773".to_string()), run.part_one.unwrap().output);
+        assert_eq!(Ok("21271939".to_string()), run.part_two.unwrap().output);
+    }
+
+    #[test]
+    fn test_table() {
+        let outcomes = [
+            Outcome::Done(Box::new(run(find(2024, 3).unwrap(), &Context::default()))),
+            Outcome::TimedOut {
+                name: "2024/04".to_string(),
+                limit: Duration::from_secs(1),
+            },
+        ];
+        let table = table(&outcomes);
+        assert_eq!(5, table.lines().count());
+        assert!(table.contains("180233229"));
+        assert!(table.contains("timed out after 1s"));
+    }
+
+    #[test]
+    fn test_run_all() {
+        let solutions = select(Some(2024), None);
+        let timeout = Some(Duration::from_secs(60));
+        let outcomes = run_all(&solutions, 3, |s| {
+            run_with_timeout(s, timeout, &Context::default())
+        });
+        assert_eq!(solutions.len(), outcomes.len());
+        assert!(outcomes.iter().all(|o| o.is_ok()));
+        assert_eq!("2024/05", outcomes[4].name());
+
+        let ctx = Context::default();
+        let outcomes = run_all(&solutions[3..4], 1, |s| {
+            run_with_timeout(s, Some(Duration::ZERO), &ctx)
+        });
+        assert!(matches!(outcomes[0], Outcome::TimedOut { .. }));
+        assert!(ctx.is_cancelled());
+    }
+
+    #[test]
+    fn test_panics() {
+        let panicking: &'static Solution = Box::leak(Box::new(Solution {
+            parse: || panic!("bad input"),
+            ..*find(2024, 1).unwrap()
+        }));
+
+        for timeout in [None, Some(Duration::from_secs(60))] {
+            let outcomes = run_all(&[panicking], 1, |s| {
+                run_with_timeout(s, timeout, &Context::default())
+            });
+            assert!(
+                matches!(outcomes[0], Outcome::Panicked { .. }),
+                "{timeout:?}"
+            );
+        }
+    }
+}
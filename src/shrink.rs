@@ -0,0 +1,119 @@
+/// Delta debugging: removes chunks of `items` while `fails` keeps returning
+/// true, until no single item can be removed.
+///
+/// `items` must fail to begin with.
+pub fn ddmin<T: Clone>(mut items: Vec<T>, mut fails: impl FnMut(&[T]) -> bool) -> Vec<T> {
+    let mut n = 2;
+    while items.len() >= 2 {
+        let chunk = items.len().div_ceil(n);
+
+        let mut reduced = false;
+        for start in (0..items.len()).step_by(chunk) {
+            let end = (start + chunk).min(items.len());
+            let complement = [&items[..start], &items[end..]].concat();
+            if fails(&complement) {
+                items = complement;
+                n = (n - 1).max(2);
+                reduced = true;
+                break;
+            }
+        }
+
+        if !reduced {
+            if n >= items.len() {
+                break;
+            }
+            n = (n * 2).min(items.len());
+        }
+    }
+
+    items
+}
+
+fn tokens(input: &str) -> Vec<(usize, String)> {
+    input
+        .lines()
+        .enumerate()
+        .flat_map(|(i, line)| line.split_whitespace().map(move |t| (i, t.to_string())))
+        .collect()
+}
+
+/// Joins tokens on the same line with a space, dropping lines left empty.
+fn join(tokens: &[(usize, String)]) -> String {
+    let mut out = String::new();
+    let mut last = None;
+    for (line, token) in tokens {
+        match last {
+            Some(l) if l == *line => out.push(' '),
+            Some(_) => out.push('\n'),
+            None => {}
+        }
+        out.push_str(token);
+        last = Some(*line);
+    }
+
+    out
+}
+
+/// Shrinks `input` to a smaller one that still `fails`, first by whole lines
+/// and then by whitespace separated tokens.
+///
+/// Returns `input` unchanged when it does not fail.
+pub fn shrink(input: &str, mut fails: impl FnMut(&str) -> bool) -> String {
+    if !fails(input) {
+        return input.to_string();
+    }
+
+    let mut current = input.to_string();
+    loop {
+        let lines = current.lines().map(String::from).collect::<Vec<_>>();
+        let lines = ddmin(lines, |l| fails(&l.join("\n")));
+        let mut next = lines.join("\n");
+
+        // tokens are joined with single spaces, which may hide the failure
+        let tokens = tokens(&next);
+        if fails(&join(&tokens)) {
+            next = join(&ddmin(tokens, |t| fails(&join(t))));
+        }
+
+        if next.len() >= current.len() {
+            return next;
+        }
+        current = next;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{ddmin, shrink};
+
+    #[test]
+    fn test_ddmin() {
+        let items = (0..1000).collect::<Vec<_>>();
+        let mut tests = 0;
+        let minimal = ddmin(items, |i| {
+            tests += 1;
+            i.contains(&13) && i.contains(&700)
+        });
+
+        assert_eq!(vec![13, 700], minimal);
+        assert!(tests < 200, "{tests} tests");
+    }
+
+    #[test]
+    fn test_shrink() {
+        let input = (0..100)
+            .map(|i| format!("{i} {} {}", i * 2, i * 3))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let minimal = shrink(&input, |s| {
+            s.lines().any(|l| l.split_whitespace().any(|t| t == "42")) && s.lines().count() > 1
+        });
+
+        assert_eq!(2, minimal.lines().count());
+        assert!(minimal.contains("42"));
+        assert!(minimal.len() <= 6, "{minimal:?}");
+
+        assert_eq!("a b", shrink("a b", |_| false));
+    }
+}
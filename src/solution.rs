@@ -0,0 +1,111 @@
+use std::str::FromStr;
+
+use crate::{budget::Budget, context::Context, generate::Rng, input::Input, visualize::Visualize};
+
+/// A parsed puzzle that can answer both parts.
+///
+/// Long-running parts should call [`Context::tick`] regularly so they report
+/// progress and stop once cancelled.
+pub trait Solve {
+    fn part_one(&self, ctx: &Context) -> anyhow::Result<String>;
+    fn part_two(&self, ctx: &Context) -> anyhow::Result<String>;
+
+    /// Queries understood by [`Solve::query`] as `(usage, description)` pairs.
+    fn queries(&self) -> &'static [(&'static str, &'static str)] {
+        &[]
+    }
+
+    /// The day's visualization, if it has one.
+    fn visualize(&self) -> Option<&dyn Visualize> {
+        None
+    }
+
+    /// Answers a question about the parsed state, `None` for an unknown command.
+    fn query(&self, _command: &str, _args: &[&str]) -> Option<anyhow::Result<String>> {
+        None
+    }
+}
+
+/// Another implementation of one part, which must agree with the [`Solve`] one.
+pub struct Variant {
+    pub name: &'static str,
+    pub part: u8,
+    pub solve: fn(&str, &Context) -> anyhow::Result<String>,
+}
+
+/// Registry entry for a single day.
+pub struct Solution {
+    pub year: u16,
+    pub day: u8,
+    pub parse: fn() -> anyhow::Result<Box<dyn Solve>>,
+    pub parse_input: fn(&str) -> anyhow::Result<Box<dyn Solve>>,
+    pub variants: &'static [Variant],
+    /// Builds a random input of the given size, see [`crate::generate`].
+    pub generate: fn(&mut Rng, usize) -> String,
+    /// Builds the inputs for `aoc scale` when growing them like `generate`
+    /// would hide what makes the day slow.
+    pub scale: Option<fn(&mut Rng, usize) -> String>,
+    /// Checked by the ignored `budget` test of the `perf-budget` feature.
+    pub budget: Budget,
+}
+
+impl Solution {
+    pub fn name(&self) -> String {
+        format!("{}/{:02}", self.year, self.day)
+    }
+
+    /// The embedded puzzle input.
+    pub fn input(&self) -> anyhow::Result<Input<'static>> {
+        Input::embedded(&format!("{}/{}.txt", self.year, self.day))
+    }
+}
+
+/// Runs a day's `new_from_input` on raw text, for [`Solution::parse_input`].
+pub(crate) fn parse<T: Solve + 'static>(
+    input: &str,
+    new: fn(&Input) -> anyhow::Result<T>,
+) -> anyhow::Result<Box<dyn Solve>> {
+    Ok(Box::new(new(&Input::new(input))?))
+}
+
+/// Parses the `i`th query argument.
+pub(crate) fn arg<T>(args: &[&str], i: usize) -> anyhow::Result<T>
+where
+    T: FromStr,
+    T::Err: std::error::Error + Send + Sync + 'static,
+{
+    let Some(arg) = args.get(i) else {
+        anyhow::bail!("missing argument {}", i + 1)
+    };
+
+    Ok(arg.parse()?)
+}
+
+pub fn solutions() -> impl Iterator<Item = &'static Solution> {
+    crate::aoc2024::SOLUTIONS.iter()
+}
+
+pub fn find(year: u16, day: u8) -> Option<&'static Solution> {
+    solutions().find(|s| s.year == year && s.day == day)
+}
+
+/// Solutions for `year`, optionally narrowed down to a single `day`.
+pub fn select(year: Option<u16>, day: Option<u8>) -> Vec<&'static Solution> {
+    solutions()
+        .filter(|s| year.is_none_or(|y| s.year == y))
+        .filter(|s| day.is_none_or(|d| s.day == d))
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::{find, select};
+
+    #[test]
+    fn test_find() {
+        let solution = find(2024, 4).unwrap();
+        assert_eq!("2024/04", solution.name());
+        assert!(find(2024, 26).is_none());
+        assert_eq!(5, select(Some(2024), None).len());
+    }
+}
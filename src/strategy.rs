@@ -0,0 +1,52 @@
+//! Proptest strategies shared by the parser tests.
+
+use proptest::{collection::vec, prelude::*};
+
+/// Horizontal whitespace between tokens: at least one space or tab.
+pub fn gap() -> impl Strategy<Value = String> {
+    "[ \t]{1,4}"
+}
+
+/// Optional horizontal whitespace around a line or separator.
+pub fn padding() -> impl Strategy<Value = String> {
+    "[ \t]{0,3}"
+}
+
+/// Joins `tokens` with `sep` surrounded by random padding.
+pub fn join_padded(tokens: Vec<String>, sep: &'static str) -> impl Strategy<Value = String> {
+    let n = tokens.len();
+    (vec(padding(), n * 2), padding(), padding()).prop_map(move |(pads, lead, trail)| {
+        let mut out = lead;
+        for (i, t) in tokens.iter().enumerate() {
+            if i > 0 {
+                out.push_str(&pads[i * 2]);
+                out.push_str(sep);
+            }
+            out.push_str(&pads[i * 2 + 1]);
+            out.push_str(t);
+        }
+        out.push_str(&trail);
+        out
+    })
+}
+
+/// Joins `tokens` with random whitespace gaps and optional leading and trailing padding.
+pub fn join_spaced(tokens: Vec<String>) -> impl Strategy<Value = String> {
+    let n = tokens.len();
+    (vec(gap(), n.saturating_sub(1)), padding(), padding()).prop_map(move |(gaps, lead, trail)| {
+        let mut out = lead;
+        for (i, t) in tokens.iter().enumerate() {
+            if i > 0 {
+                out.push_str(&gaps[i - 1]);
+            }
+            out.push_str(t);
+        }
+        out.push_str(&trail);
+        out
+    })
+}
+
+/// Puzzle-sized location ids and levels.
+pub fn number() -> impl Strategy<Value = i32> {
+    0..100_000
+}
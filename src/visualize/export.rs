@@ -0,0 +1,280 @@
+use std::{fmt::Write as _, fs::File, io::BufWriter, path::Path, time::Duration};
+
+use image::{
+    Delay, Rgba, RgbaImage,
+    codecs::gif::{GifEncoder, Repeat},
+};
+
+use super::{Frame, Highlight, clamp_fps, font};
+
+const BACKGROUND: [u8; 3] = [0x1e, 0x1e, 0x2e];
+const TEXT: [u8; 3] = [0xcd, 0xd6, 0xf4];
+
+fn fill(highlight: Option<Highlight>) -> Option<[u8; 3]> {
+    match highlight {
+        Some(Highlight::Active) => Some([0xf9, 0xe2, 0xaf]),
+        Some(Highlight::Match) => Some([0xa6, 0xe3, 0xa1]),
+        Some(Highlight::Dim) => Some([0x45, 0x47, 0x5a]),
+        None => None,
+    }
+}
+
+fn hex([r, g, b]: [u8; 3]) -> String {
+    format!("#{r:02x}{g:02x}{b:02x}")
+}
+
+fn escape(c: char) -> String {
+    match c {
+        '<' => "&lt;".to_string(),
+        '>' => "&gt;".to_string(),
+        '&' => "&amp;".to_string(),
+        '"' => "&quot;".to_string(),
+        c => c.to_string(),
+    }
+}
+
+/// Renders `frame` as an SVG document with `cell` pixels per grid cell.
+pub fn svg(frame: &Frame, cell: u32) -> String {
+    let (rows, cols) = (frame.rows() as u32, frame.cols() as u32);
+    let caption = if frame.annotation.is_empty() {
+        0
+    } else {
+        cell * 2
+    };
+    let (width, height) = (cols * cell, rows * cell + caption);
+
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" viewBox="0 0 {width} {height}">"#
+    );
+    let _ = writeln!(
+        out,
+        r#"<rect width="{width}" height="{height}" fill="{}"/>"#,
+        hex(BACKGROUND)
+    );
+
+    let highlights = frame.highlight_map();
+    for (row, (line, marks)) in frame.grid.iter().zip(highlights).enumerate() {
+        for (col, (c, h)) in line.iter().zip(marks).enumerate() {
+            let (x, y) = (col as u32 * cell, row as u32 * cell);
+            if let Some(color) = fill(h) {
+                let _ = writeln!(
+                    out,
+                    r#"<rect x="{x}" y="{y}" width="{cell}" height="{cell}" fill="{}"/>"#,
+                    hex(color)
+                );
+            }
+
+            let color = if h.is_some_and(|h| h != Highlight::Dim) {
+                BACKGROUND
+            } else {
+                TEXT
+            };
+            let _ = writeln!(
+                out,
+                r#"<text x="{}" y="{}" font-family="monospace" font-size="{}" text-anchor="middle" dominant-baseline="central" fill="{}">{}</text>"#,
+                x + cell / 2,
+                y + cell / 2,
+                cell * 4 / 5,
+                hex(color),
+                escape(*c)
+            );
+        }
+    }
+
+    if caption > 0 {
+        let mut annotation = String::new();
+        frame
+            .annotation
+            .chars()
+            .for_each(|c| annotation.push_str(&escape(c)));
+        let _ = writeln!(
+            out,
+            r#"<text x="{}" y="{}" font-family="monospace" font-size="{cell}" fill="{}">{annotation}</text>"#,
+            cell / 2,
+            rows * cell + cell,
+            hex(TEXT)
+        );
+    }
+
+    out.push_str("</svg>\n");
+
+    out
+}
+
+/// Renders `frame` as an image with `cell` pixels per grid cell.
+///
+/// Letters come from a built-in 5x7 bitmap font, scaled by whole pixels to
+/// fit the cell with a margin and centred in it.
+pub fn image(frame: &Frame, cell: u32) -> RgbaImage {
+    let cell = cell.max(1);
+    let (rows, cols) = (frame.rows() as u32, frame.cols() as u32);
+    let [r, g, b] = BACKGROUND;
+    let mut img = RgbaImage::from_pixel(
+        (cols * cell).max(1),
+        (rows * cell).max(1),
+        Rgba([r, g, b, 0xff]),
+    );
+
+    let scale = (cell / (font::HEIGHT + 2)).max(1);
+    let left = (cell as i32 - (font::WIDTH * scale) as i32) / 2;
+    let top = (cell as i32 - (font::HEIGHT * scale) as i32) / 2;
+
+    let highlights = frame.highlight_map();
+    for (row, (line, marks)) in frame.grid.iter().zip(highlights).enumerate() {
+        for (col, (c, h)) in line.iter().zip(marks).enumerate() {
+            let (x0, y0) = (col as u32 * cell, row as u32 * cell);
+            let background = fill(h).unwrap_or(BACKGROUND);
+            let glyph = if h.is_some_and(|h| h != Highlight::Dim) {
+                BACKGROUND
+            } else {
+                TEXT
+            };
+
+            for y in 0..cell {
+                for x in 0..cell {
+                    let (gx, gy) = (x as i32 - left, y as i32 - top);
+                    let inked =
+                        gx >= 0 && gy >= 0 && font::lit(*c, gx as u32 / scale, gy as u32 / scale);
+                    let [r, g, b] = if inked { glyph } else { background };
+                    img.put_pixel(x0 + x, y0 + y, Rgba([r, g, b, 0xff]));
+                }
+            }
+        }
+    }
+
+    img
+}
+
+pub fn write_svg(frame: &Frame, cell: u32, path: &Path) -> anyhow::Result<()> {
+    std::fs::write(path, svg(frame, cell))?;
+
+    Ok(())
+}
+
+pub fn write_png(frame: &Frame, cell: u32, path: &Path) -> anyhow::Result<()> {
+    image(frame, cell).save_with_format(path, image::ImageFormat::Png)?;
+
+    Ok(())
+}
+
+/// Assembles `frames` into an animated GIF playing at `fps`, within the
+/// speeds the terminal player supports.
+pub fn write_gif(
+    frames: impl Iterator<Item = Frame>,
+    cell: u32,
+    fps: f64,
+    path: &Path,
+) -> anyhow::Result<usize> {
+    let mut encoder = GifEncoder::new(BufWriter::new(File::create(path)?));
+    encoder.set_repeat(Repeat::Infinite)?;
+
+    let delay = Delay::from_saturating_duration(Duration::from_secs_f64(1.0 / clamp_fps(fps)));
+    let mut count = 0;
+    for frame in frames {
+        encoder.encode_frame(image::Frame::from_parts(image(&frame, cell), 0, 0, delay))?;
+        count += 1;
+    }
+
+    Ok(count)
+}
+
+/// Writes each of `frames` next to `path` as `<stem>-0001.<ext>`, choosing
+/// SVG or PNG by the extension of `path`.
+pub fn write_sequence(
+    frames: impl Iterator<Item = Frame>,
+    cell: u32,
+    path: &Path,
+) -> anyhow::Result<usize> {
+    let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("frame");
+    let ext = path.extension().and_then(|s| s.to_str()).unwrap_or("svg");
+
+    let mut count = 0;
+    for (i, frame) in frames.enumerate() {
+        let file = path.with_file_name(format!("{stem}-{:04}.{ext}", i + 1));
+        match ext {
+            "png" => write_png(&frame, cell, &file)?,
+            "svg" => write_svg(&frame, cell, &file)?,
+            _ => anyhow::bail!("unsupported frame format `{ext}`, use svg or png"),
+        }
+        count += 1;
+    }
+
+    Ok(count)
+}
+
+#[cfg(test)]
+mod test {
+    use std::{env, fs::File, io::BufReader, process, time::Duration};
+
+    use image::{AnimationDecoder, codecs::gif::GifDecoder};
+
+    use super::{image, svg, write_gif};
+    use crate::visualize::{Frame, Highlight};
+
+    fn frame() -> Frame {
+        let mut frame = Frame::new(vec!["X<".chars().collect(), "AS".chars().collect()]);
+        frame.highlights = vec![(1, 0, Highlight::Match)];
+        frame.annotation = "1 & done".to_string();
+        frame
+    }
+
+    #[test]
+    fn test_svg() {
+        let svg = svg(&frame(), 10);
+        assert!(
+            svg.starts_with(r#"<svg xmlns="http://www.w3.org/2000/svg" width="20" height="40""#)
+        );
+        assert!(svg.contains(r##"<rect x="0" y="10" width="10" height="10" fill="#a6e3a1"/>"##));
+        assert!(svg.contains(">&lt;</text>"));
+        assert!(svg.contains(">1 &amp; done</text>"));
+        assert!(svg.ends_with("</svg>\n"));
+    }
+
+    #[test]
+    fn test_image() {
+        let img = image(&frame(), 12);
+        assert_eq!((24, 24), img.dimensions());
+        // `X` in the top left cell starts with a stroke at its top left corner
+        assert_eq!([0xcd, 0xd6, 0xf4, 0xff], img.get_pixel(3, 2).0);
+        assert_eq!([0x1e, 0x1e, 0x2e, 0xff], img.get_pixel(4, 2).0);
+        // and `A` on the highlight is drawn in the background colour
+        assert_eq!([0xa6, 0xe3, 0xa1, 0xff], img.get_pixel(0, 12).0);
+        assert_eq!([0x1e, 0x1e, 0x2e, 0xff], img.get_pixel(3, 15).0);
+
+        let glyphs = |c: char| {
+            image(&Frame::new(vec![vec![c]]), 18)
+                .pixels()
+                .filter(|p| p.0 == [0xcd, 0xd6, 0xf4, 0xff])
+                .count()
+        };
+        assert_eq!(0, glyphs(' '));
+        assert_eq!(4 * 13, glyphs('X'));
+        assert_ne!(glyphs('S'), glyphs('X'));
+    }
+
+    fn gif_delays(fps: f64) -> Vec<Duration> {
+        let path = env::temp_dir().join(format!("aoc_export_{}_{fps}.gif", process::id()));
+        assert_eq!(
+            2,
+            write_gif([frame(), frame()].into_iter(), 2, fps, &path).unwrap()
+        );
+
+        let decoder = GifDecoder::new(BufReader::new(File::open(&path).unwrap())).unwrap();
+        let delays = decoder
+            .into_frames()
+            .map(|f| Duration::from(f.unwrap().delay()))
+            .collect();
+        let _ = std::fs::remove_file(&path);
+
+        delays
+    }
+
+    #[test]
+    fn test_gif_delay() {
+        // clamped to the slowest player speed instead of dividing by zero
+        assert_eq!(vec![Duration::from_secs(2); 2], gif_delays(0.25));
+        assert_eq!(vec![Duration::from_millis(400); 2], gif_delays(2.5));
+    }
+}
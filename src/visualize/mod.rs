@@ -0,0 +1,83 @@
+pub mod export;
+mod font;
+pub mod terminal;
+
+/// Slowest and fastest playback, in frames per second.
+pub const MIN_FPS: f64 = 0.5;
+pub const MAX_FPS: f64 = 240.0;
+
+/// `fps` brought within [`MIN_FPS`] and [`MAX_FPS`].
+pub fn clamp_fps(fps: f64) -> f64 {
+    if fps.is_nan() {
+        return MIN_FPS;
+    }
+
+    fps.clamp(MIN_FPS, MAX_FPS)
+}
+
+/// How a highlighted cell is drawn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Highlight {
+    /// Where the solver is currently looking.
+    Active,
+    /// Part of an answer found so far.
+    Match,
+    /// Visited without result.
+    Dim,
+}
+
+/// A snapshot of a grid with highlighted cells and a caption.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    pub grid: Vec<Vec<char>>,
+    /// `(row, col)` of each highlighted cell, later entries win.
+    pub highlights: Vec<(usize, usize, Highlight)>,
+    pub annotation: String,
+}
+
+impl Frame {
+    pub fn new(grid: Vec<Vec<char>>) -> Self {
+        Self {
+            grid,
+            highlights: vec![],
+            annotation: String::new(),
+        }
+    }
+
+    pub fn rows(&self) -> usize {
+        self.grid.len()
+    }
+
+    pub fn cols(&self) -> usize {
+        self.grid.iter().map(|r| r.len()).max().unwrap_or_default()
+    }
+
+    /// The highlight of every cell, indexed `[row][col]`.
+    pub fn highlight_map(&self) -> Vec<Vec<Option<Highlight>>> {
+        let mut map = self
+            .grid
+            .iter()
+            .map(|r| vec![None; r.len()])
+            .collect::<Vec<_>>();
+        for &(row, col, h) in &self.highlights {
+            if let Some(cell) = map.get_mut(row).and_then(|r| r.get_mut(col)) {
+                *cell = Some(h);
+            }
+        }
+
+        map
+    }
+
+    /// First active cell, used to keep the interesting part of a large grid in view.
+    pub fn focus(&self) -> Option<(usize, usize)> {
+        self.highlights
+            .iter()
+            .find(|(_, _, h)| *h == Highlight::Active)
+            .map(|&(row, col, _)| (row, col))
+    }
+}
+
+/// Implemented by solvers that can show how they reach their answer.
+pub trait Visualize {
+    fn frames(&self) -> Box<dyn Iterator<Item = Frame> + '_>;
+}
@@ -0,0 +1,279 @@
+use std::{
+    io::{Write, stdout},
+    time::{Duration, Instant},
+};
+
+use crossterm::{
+    cursor,
+    event::{self, Event, KeyCode, KeyEventKind},
+    execute, queue,
+    terminal::{self, ClearType},
+};
+
+use super::{Frame, Highlight, clamp_fps};
+
+const RESET: &str = "\x1b[0m";
+
+fn style(highlight: Highlight) -> &'static str {
+    match highlight {
+        Highlight::Active => "\x1b[30;43m",
+        Highlight::Match => "\x1b[30;42m",
+        Highlight::Dim => "\x1b[2m",
+    }
+}
+
+/// Top-left cell of a `rows` x `cols` window into `frame` that keeps its focus in view.
+pub fn viewport(frame: &Frame, rows: usize, cols: usize) -> (usize, usize) {
+    let Some((row, col)) = frame.focus() else {
+        return (0, 0);
+    };
+
+    let start = |pos: usize, size: usize, window: usize| {
+        if size <= window {
+            0
+        } else {
+            pos.saturating_sub(window / 2).min(size - window)
+        }
+    };
+
+    (
+        start(row, frame.rows(), rows),
+        start(col, frame.cols(), cols),
+    )
+}
+
+/// Renders at most `rows` x `cols` cells of `frame` with ANSI colours, one line per row.
+pub fn render(frame: &Frame, rows: usize, cols: usize) -> Vec<String> {
+    let (top, left) = viewport(frame, rows, cols);
+    let highlights = frame.highlight_map();
+
+    frame
+        .grid
+        .iter()
+        .zip(highlights)
+        .skip(top)
+        .take(rows)
+        .map(|(line, marks)| {
+            let mut out = String::new();
+            for (c, h) in line.iter().zip(marks).skip(left).take(cols) {
+                match h {
+                    Some(h) => {
+                        out.push_str(style(h));
+                        out.push(*c);
+                        out.push_str(RESET);
+                    }
+                    None => out.push(*c),
+                }
+            }
+
+            out
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    TogglePause,
+    Step,
+    Back,
+    Faster,
+    Slower,
+    Quit,
+}
+
+impl Action {
+    fn from_key(key: KeyCode) -> Option<Self> {
+        match key {
+            KeyCode::Char(' ') => Some(Action::TogglePause),
+            KeyCode::Right | KeyCode::Char('n') => Some(Action::Step),
+            KeyCode::Left | KeyCode::Char('p') => Some(Action::Back),
+            KeyCode::Char('+') | KeyCode::Char('=') => Some(Action::Faster),
+            KeyCode::Char('-') => Some(Action::Slower),
+            KeyCode::Char('q') | KeyCode::Esc => Some(Action::Quit),
+            _ => None,
+        }
+    }
+}
+
+/// Playback position and speed, independent of the terminal.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Player {
+    pub index: usize,
+    pub paused: bool,
+    pub fps: f64,
+    pub done: bool,
+}
+
+impl Player {
+    pub fn new(fps: f64) -> Self {
+        Self {
+            index: 0,
+            paused: false,
+            fps: clamp_fps(fps),
+            done: false,
+        }
+    }
+
+    pub fn delay(&self) -> Duration {
+        Duration::from_secs_f64(1.0 / self.fps)
+    }
+
+    /// Applies `action`, `available` is the number of frames that can be shown.
+    pub fn apply(&mut self, action: Action, available: usize) {
+        match action {
+            Action::TogglePause => self.paused = !self.paused,
+            Action::Step => {
+                self.paused = true;
+                self.advance(available);
+            }
+            Action::Back => {
+                self.paused = true;
+                self.index = self.index.saturating_sub(1);
+            }
+            Action::Faster => self.fps = clamp_fps(self.fps * 2.0),
+            Action::Slower => self.fps = clamp_fps(self.fps / 2.0),
+            Action::Quit => self.done = true,
+        }
+    }
+
+    /// Moves to the next frame, pausing on the last one.
+    pub fn advance(&mut self, available: usize) {
+        if self.index + 1 < available {
+            self.index += 1;
+        } else {
+            self.paused = true;
+        }
+    }
+}
+
+/// Plays `frames` in the terminal until the user quits.
+///
+/// Space pauses, `n`/`p` or the arrow keys step, `+`/`-` change the speed and
+/// `q` quits.
+pub fn play(frames: impl Iterator<Item = Frame>, fps: f64) -> anyhow::Result<()> {
+    let mut out = stdout();
+    terminal::enable_raw_mode()?;
+    execute!(out, terminal::EnterAlternateScreen, cursor::Hide)?;
+
+    let res = play_loop(frames, fps, &mut out);
+
+    execute!(out, cursor::Show, terminal::LeaveAlternateScreen)?;
+    terminal::disable_raw_mode()?;
+
+    res
+}
+
+fn play_loop(
+    mut frames: impl Iterator<Item = Frame>,
+    fps: f64,
+    out: &mut impl Write,
+) -> anyhow::Result<()> {
+    let mut buffer = vec![];
+    let mut exhausted = false;
+    let mut player = Player::new(fps);
+
+    while !player.done {
+        // pull one frame ahead so stepping forward knows whether more exist
+        while !exhausted && buffer.len() <= player.index + 1 {
+            match frames.next() {
+                Some(frame) => buffer.push(frame),
+                None => exhausted = true,
+            }
+        }
+        let Some(frame) = buffer.get(player.index) else {
+            break;
+        };
+
+        let (cols, rows) = terminal::size()?;
+        let lines = render(frame, rows.saturating_sub(2) as usize, cols as usize);
+
+        queue!(out, cursor::MoveTo(0, 0), terminal::Clear(ClearType::All))?;
+        for line in lines {
+            write!(out, "{line}\r\n")?;
+        }
+        let total = if exhausted {
+            buffer.len().to_string()
+        } else {
+            "?".to_string()
+        };
+        write!(
+            out,
+            "{}\r\nframe {}/{total} {:.1} fps{} | space pause, n/p step, +/- speed, q quit",
+            frame.annotation,
+            player.index + 1,
+            player.fps,
+            if player.paused { " paused" } else { "" },
+        )?;
+        out.flush()?;
+
+        let deadline = Instant::now() + player.delay();
+        loop {
+            let timeout = if player.paused {
+                Duration::from_millis(250)
+            } else {
+                deadline.saturating_duration_since(Instant::now())
+            };
+
+            if event::poll(timeout)? {
+                if let Event::Key(key) = event::read()?
+                    && key.kind == KeyEventKind::Press
+                    && let Some(action) = Action::from_key(key.code)
+                {
+                    player.apply(action, buffer.len());
+                    break;
+                }
+            } else if !player.paused {
+                player.advance(buffer.len());
+                break;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Action, Player, render, viewport};
+    use crate::visualize::{Frame, Highlight};
+
+    fn frame() -> Frame {
+        let mut frame = Frame::new(vec!["abcd".chars().collect(), "efgh".chars().collect()]);
+        frame.highlights = vec![(1, 2, Highlight::Active), (0, 0, Highlight::Match)];
+        frame
+    }
+
+    #[test]
+    fn test_render() {
+        let lines = render(&frame(), 10, 10);
+        assert_eq!(2, lines.len());
+        assert_eq!("\x1b[30;42ma\x1b[0mbcd", lines[0]);
+        assert_eq!("ef\x1b[30;43mg\x1b[0mh", lines[1]);
+
+        assert_eq!((1, 1), viewport(&frame(), 1, 2));
+        assert_eq!(vec!["f\x1b[30;43mg\x1b[0m"], render(&frame(), 1, 2));
+    }
+
+    #[test]
+    fn test_player() {
+        let mut player = Player::new(10.0);
+        player.advance(3);
+        player.advance(3);
+        assert_eq!(2, player.index);
+        assert!(!player.paused);
+
+        player.advance(3);
+        assert_eq!(2, player.index);
+        assert!(player.paused);
+
+        player.apply(Action::Back, 3);
+        player.apply(Action::Faster, 3);
+        assert_eq!(1, player.index);
+        assert_eq!(20.0, player.fps);
+
+        player.apply(Action::TogglePause, 3);
+        assert!(!player.paused);
+        player.apply(Action::Quit, 3);
+        assert!(player.done);
+    }
+}